@@ -0,0 +1,172 @@
+#[cfg(any(test, feature = "alloc-stats"))]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Allocation counts for one measured closure. `peak` is the most memory it held at once, on top
+/// of whatever was already live when it started.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+    pub peak: u64,
+}
+
+#[derive(Clone, Copy)]
+struct Counters {
+    allocations: u64,
+    bytes: u64,
+    live: i64,
+    peak: i64,
+}
+
+// Counted per thread so that days running side by side don't mix their numbers. Memory freed on
+// another thread than the one that allocated it is credited to the freeing thread.
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            allocations: 0,
+            bytes: 0,
+            live: 0,
+            peak: 0,
+        })
+    };
+}
+
+#[cfg(any(test, feature = "alloc-stats"))]
+fn record(update: impl FnOnce(&mut Counters)) {
+    // The thread-local is gone while a thread shuts down; those last frees don't matter
+    let _ = COUNTERS.try_with(|cell| {
+        let mut counters = cell.get();
+        update(&mut counters);
+        counters.peak = counters.peak.max(counters.live);
+        cell.set(counters);
+    });
+}
+
+/// Forwards to the system allocator, counting every allocation. Installed as the global allocator
+/// by the `alloc-stats` feature; a `realloc` counts as a new allocation of the new size.
+#[cfg(any(test, feature = "alloc-stats"))]
+pub struct CountingAllocator;
+
+#[cfg(any(test, feature = "alloc-stats"))]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(|counters| counters.allocated(layout.size(), 0));
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(|counters| counters.allocated(layout.size(), 0));
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(|counters| counters.live -= layout.size() as i64);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(|counters| counters.allocated(new_size, layout.size()));
+        }
+        new_ptr
+    }
+}
+
+#[cfg(any(test, feature = "alloc-stats"))]
+impl Counters {
+    fn allocated(&mut self, size: usize, replaced: usize) {
+        self.allocations += 1;
+        self.bytes += size as u64;
+        self.live += size as i64 - replaced as i64;
+    }
+}
+
+/// Runs `f` and counts what it allocated on this thread. Without the `alloc-stats` feature nothing
+/// is counted and the stats are `None`.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    if cfg!(feature = "alloc-stats") {
+        let (result, stats) = count(f);
+        (result, Some(stats))
+    } else {
+        (f(), None)
+    }
+}
+
+fn count<R>(f: impl FnOnce() -> R) -> (R, AllocStats) {
+    let before = COUNTERS.with(|cell| {
+        let mut counters = cell.get();
+        counters.peak = counters.live;
+        cell.set(counters);
+        counters
+    });
+    let result = f();
+    let after = COUNTERS.with(Cell::get);
+    let stats = AllocStats {
+        allocations: after.allocations - before.allocations,
+        bytes: after.bytes - before.bytes,
+        peak: (after.peak - before.live).max(0) as u64,
+    };
+    (result, stats)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1 << 10 {
+        format!("{bytes}B")
+    } else if bytes < 1 << 20 {
+        format!("{:.1}KiB", bytes as f64 / (1u64 << 10) as f64)
+    } else {
+        format!("{:.1}MiB", bytes as f64 / (1u64 << 20) as f64)
+    }
+}
+
+impl std::fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocs, {}, peak {}",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+#[cfg(test)]
+mod counting_allocator {
+    use super::*;
+
+    // Calls the allocator directly, so this works whether or not it is the global allocator
+    #[test]
+    fn test_count() {
+        let layout = Layout::from_size_align(100, 8).unwrap();
+        let ((), stats) = count(|| unsafe {
+            let first = CountingAllocator.alloc(layout);
+            let second = CountingAllocator.alloc(layout);
+            CountingAllocator.dealloc(first, layout);
+            let second = CountingAllocator.realloc(second, layout, 300);
+            CountingAllocator.dealloc(second, Layout::from_size_align(300, 8).unwrap());
+        });
+        assert_eq!(stats.allocations, 3);
+        assert_eq!(stats.bytes, 500);
+        // Only the reallocated block was live at its end
+        assert!(stats.peak >= 300);
+    }
+
+    #[test]
+    fn test_display() {
+        let stats = AllocStats {
+            allocations: 12,
+            bytes: 3 << 20,
+            peak: 1536,
+        };
+        assert_eq!(stats.to_string(), "12 allocs, 3.0MiB, peak 1.5KiB");
+    }
+}
@@ -0,0 +1,198 @@
+use crate::cli::{Failure, Part, RunOptions};
+use crate::input;
+use crate::runner::{self, format_duration};
+use crate::solution::DynSolution;
+use std::hint::black_box;
+use std::io;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BenchOptions {
+    pub warmup: usize,
+    pub samples: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            warmup: 2,
+            samples: 10,
+        }
+    }
+}
+
+// A phase name and its stats, or why it couldn't be timed
+pub type PhaseResult = (String, Result<Stats, String>);
+
+/// Summary of the samples left after discarding outliers
+#[derive(Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    pub outliers: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub std_dev: Duration,
+}
+
+// Samples outside Tukey's fences, 1.5 interquartile ranges beyond the first or third quartile, are
+// treated as outliers (a context switch, a page fault...) and left out of the summary
+pub fn summarize(samples: &[Duration]) -> Stats {
+    assert!(!samples.is_empty(), "can't summarize zero samples");
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    let q1 = percentile(&sorted, 25.0).as_secs_f64();
+    let q3 = percentile(&sorted, 75.0).as_secs_f64();
+    let fence = 1.5 * (q3 - q1);
+    let kept = sorted
+        .iter()
+        .copied()
+        .filter(|sample| {
+            let sample = sample.as_secs_f64();
+            sample >= q1 - fence && sample <= q3 + fence
+        })
+        .collect::<Vec<_>>();
+
+    let mean = kept.iter().sum::<Duration>() / kept.len() as u32;
+    let variance = if kept.len() > 1 {
+        kept.iter()
+            .map(|sample| (sample.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / (kept.len() - 1) as f64
+    } else {
+        0.0
+    };
+    Stats {
+        samples: kept.len(),
+        outliers: sorted.len() - kept.len(),
+        min: kept[0],
+        median: percentile(&kept, 50.0),
+        mean,
+        p95: percentile(&kept, 95.0),
+        std_dev: Duration::from_secs_f64(variance.sqrt()),
+    }
+}
+
+// Nearest-rank percentile of already sorted samples
+fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn sample<R>(options: &BenchOptions, mut f: impl FnMut() -> R) -> Stats {
+    for _ in 0..options.warmup {
+        black_box(f());
+    }
+    let samples = (0..options.samples.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    summarize(&samples)
+}
+
+/// Stats for parsing and each part that was asked for. A part that fails or panics is reported
+/// with its message instead of stats. The input is read once and parsed from memory, so that disk reads
+/// don't skew the samples and stdin can be benchmarked too.
+pub fn bench(
+    solution: &dyn DynSolution,
+    options: &RunOptions,
+    bench_options: &BenchOptions,
+) -> Result<Vec<PhaseResult>, Failure> {
+    let input = options.input_path(solution.day());
+    let contents = input::read(&input).map_err(|error| match error.kind() {
+        io::ErrorKind::NotFound => Failure::MissingInput(input.clone(), error),
+        _ => Failure::Solver(error.to_string()),
+    })?;
+    let parsed = solution
+        .parse(&mut contents.as_slice())
+        .map_err(|error| Failure::Solver(error.in_file(&input).to_string()))?;
+
+    let mut results = vec![(
+        "parse".to_owned(),
+        Ok(sample(bench_options, || {
+            solution.parse(&mut contents.as_slice())
+        })),
+    )];
+    for part in [Part::One, Part::Two] {
+        if !options.wants(part) {
+            continue;
+        }
+        let run_part = || match part {
+            Part::One => solution.part1(parsed.as_ref()),
+            Part::Two => solution.part2(parsed.as_ref()),
+        };
+        // Run once outside of the timings so that a broken part doesn't panic on every sample
+        let stats = runner::run_part(solution, parsed.as_ref(), part)
+            .map(|_| sample(bench_options, run_part));
+        results.push((format!("part {}", part.number()), stats));
+    }
+    Ok(results)
+}
+
+pub fn format_results(results: &[PhaseResult]) -> String {
+    let header = [
+        "Phase", "Samples", "Outliers", "Min", "Median", "Mean", "p95", "Std dev",
+    ]
+    .map(String::from);
+    let rows = results
+        .iter()
+        .map(|(phase, stats)| match stats {
+            Ok(stats) => vec![
+                phase.clone(),
+                stats.samples.to_string(),
+                stats.outliers.to_string(),
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.mean),
+                format_duration(stats.p95),
+                format_duration(stats.std_dev),
+            ],
+            Err(message) => vec![phase.clone(), format!("ERROR: {message}")],
+        })
+        .collect::<Vec<_>>();
+    runner::format_rows(&header, &rows)
+}
+
+#[cfg(test)]
+mod test_summarize {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values
+            .iter()
+            .map(|value| Duration::from_millis(*value))
+            .collect()
+    }
+
+    #[test]
+    fn test_summarize() {
+        let stats = summarize(&millis(&[4, 2, 3, 5, 1]));
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        assert_eq!(stats.p95, Duration::from_millis(5));
+        // sqrt(10 / 4) ms
+        assert_eq!(stats.std_dev.as_micros(), 1581);
+    }
+
+    #[test]
+    fn test_discards_outliers() {
+        let stats = summarize(&millis(&[10, 11, 10, 12, 11, 10, 95]));
+        assert_eq!(stats.samples, 6);
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.p95, Duration::from_millis(12));
+    }
+
+    #[test]
+    fn test_single_sample() {
+        let stats = summarize(&millis(&[7]));
+        assert_eq!(stats.median, Duration::from_millis(7));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+    }
+}
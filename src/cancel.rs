@@ -0,0 +1,118 @@
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Asks a running solver to stop, either when `cancel` is called from any thread or once the
+/// deadline has passed. Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_deadline(deadline: Instant) -> Self {
+        CancellationToken {
+            cancelled: Arc::default(),
+            deadline: Some(deadline),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+        let expired = self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+        if expired {
+            self.cancel();
+        }
+        expired
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+    static POLLS: Cell<u32> = const { Cell::new(0) };
+    // Once a cancellation has been seen, every later check sees it straight away
+    static SEEN: Cell<bool> = const { Cell::new(false) };
+}
+
+// Reading the clock on every check would slow the tightest loops down noticeably
+const POLLS_PER_CHECK: u32 = 1024;
+
+// Puts the previous token back even if the closure panics
+struct Restore(Option<CancellationToken>, bool);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+        SEEN.set(self.1);
+    }
+}
+
+/// Runs `f` with `token` as the one `cancelled` checks on this thread
+pub fn scope<R>(token: &CancellationToken, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT.with(|current| current.borrow_mut().replace(token.clone()));
+    let _restore = Restore(previous, SEEN.replace(false));
+    f()
+}
+
+/// Whether the solver running on this thread has been asked to stop. Long loops check this and
+/// bail out early with whatever they have; the runner throws that result away and reports a
+/// timeout instead. Only every 1024th call looks at the token, so it is cheap enough for inner
+/// loops. Always false outside of `scope`.
+pub fn cancelled() -> bool {
+    if SEEN.get() {
+        return true;
+    }
+    let polls = POLLS.get().wrapping_add(1);
+    POLLS.set(polls);
+    if !polls.is_multiple_of(POLLS_PER_CHECK) {
+        return false;
+    }
+    let cancelled = CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    });
+    SEEN.set(cancelled);
+    cancelled
+}
+
+#[cfg(test)]
+mod test_cancel {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_scope() {
+        assert!(!cancelled());
+        let token = CancellationToken::new();
+        scope(&token, || {
+            assert!(!cancelled());
+            token.clone().cancel();
+            assert!((0..POLLS_PER_CHECK).any(|_| cancelled()));
+        });
+        assert!(!cancelled());
+    }
+
+    #[test]
+    fn test_deadline() {
+        let token = CancellationToken::with_deadline(Instant::now() + Duration::from_secs(60));
+        assert!(!token.is_cancelled());
+        let token = CancellationToken::with_deadline(Instant::now());
+        assert!(token.is_cancelled());
+    }
+}
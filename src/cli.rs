@@ -0,0 +1,1016 @@
+use crate::bench::BenchOptions;
+use crate::client::ClientOptions;
+use crate::config::Config;
+use crate::input::{InputResolver, INPUT_FILE, STDIN};
+use crate::log::{Level, LogOptions};
+use crate::output::Format;
+use crate::runner::TIMEOUT;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+pub const USAGE: &str =
+    "Usage: aoc run <day> [--part 1|2] [--input PATH] [--input-dir DIR] [--format FORMAT] [--timeout MS]
+               [--phases]
+       aoc all [--part 1|2] [--input-dir DIR] [--format FORMAT] [--jobs N] [--timeout MS] [--phases]
+       aoc verify [--input-dir DIR] [--answers PATH] [--timeout MS]
+       aoc bench <day> [--part 1|2] [--input PATH] [--input-dir DIR] [--warmup N] [--samples N]
+       aoc watch <day> [--part 1|2] [--input PATH] [--input-dir DIR] [--interval MS]
+       aoc new <day>
+       aoc fetch <day> [--input-dir DIR] [--year YEAR] [--session-file PATH] [--base-url URL]
+       aoc submit <day> <part> [--input PATH] [--input-dir DIR] [--year YEAR] [--session-file PATH]
+                  [--base-url URL]
+       aoc extract <day> <page.html> [--input-dir DIR]
+       aoc record <day> [--input-dir DIR] [--year YEAR]
+       aoc inspect <day> [--input PATH] [--input-dir DIR]
+       aoc --help
+
+Every command also takes -v (debug) or -vv (trace) to log what the solvers are doing to stderr,
+-q to log nothing but the final error, and --log-day N[,N...] to only log those days.
+
+With no arguments, starts an interactive session: pick a day with day N, then run parts, switch
+inputs or show the parsed input without re-reading the file. Type help for the commands.
+
+Inputs are read from <DIR>/dayN/input.txt, or from PATH with --input. A PATH of - reads stdin.
+DIR defaults to $AOC_INPUT_DIR, then to the src directory of this repository.
+
+FORMAT is text (the default), json or csv. json and csv print one record per day and part with
+the answer, parse and part timings in microseconds, and any error.
+
+all runs the days on N threads, one per CPU by default, and prints them in day order.
+
+--timeout cancels a day once it has run for MS milliseconds and reports it as TIMEOUT. Solvers
+stop at their next cancellation check, so a loop without one still runs to the end.
+
+--phases shows how long reading the input, parsing and each part took, along with the steps
+inside them that the solvers time, and points out a day that reads its input more than once.
+
+verify checks every day against the expected answers in PATH, which defaults to
+<DIR>/answers.txt.
+
+bench times parsing and each part over N samples (10 by default) after a warm-up (2 runs by
+default) and reports min, median, mean, p95 and standard deviation with outliers discarded.
+
+watch polls the files in <DIR>/dayN every MS milliseconds (500 by default) and re-runs the day
+whenever one of them changes, showing how the answers changed.
+
+new creates src/dayN with a solution, parser, model and empty example, and registers the day in
+src/lib.rs. It refuses to touch a day that already exists.
+
+fetch downloads the day's input to <DIR>/dayN/input.txt unless it is already there, with the
+session cookie from $AOC_SESSION or PATH (~/.config/aoc/session by default). YEAR defaults to
+2024 and URL to https://adventofcode.com. Requests are spaced at least 5 seconds apart.
+
+submit runs the part and sends its answer, then reports whether it was right, too high, too low
+or has to wait. Every verdict is kept in <DIR>/submissions.txt, and an answer that was already
+judged, or lies beyond an earlier too high or too low, is refused without being sent.
+
+extract reads a saved puzzle page and writes its examples to <DIR>/dayN/example.txt (example2.txt
+and so on for more) and their answers to <DIR>/answers.txt. Fixtures with other contents and
+conflicting answers are left alone and reported.
+
+record runs the day on its input and, for each part whose answer submissions.txt shows was
+accepted, stores a salted SHA-256 digest of it in <DIR>/answers.txt, replacing a written-out
+answer. verify accepts answers written as sha256:<salt>:<digest> and compares digests, so the
+file can be committed without giving the answers away.
+
+inspect parses the day's input and prints figures about its shape: lines, grid size for grid
+days, and what the day counts, such as rules and update lengths for day 5. Non-ASCII characters,
+blank lines at the end, ragged grid lines and parse errors are listed with their line numbers, and
+any of them makes it exit with status 5.
+
+Defaults for the year, DIR, FORMAT, the timeout, the number of jobs and the session file can be
+set in aoc.toml, in the repository root or in ~/.config/aoc ($XDG_CONFIG_HOME/aoc), as year,
+input_dir, format, timeout, jobs and session_file. contact, an email address or URL, is sent with
+every request to the site so that its admins can reach you. The user's file wins over the
+repository's and flags win over both.
+
+Exit codes:
+  0  success
+  1  a solver failed
+  2  invalid command line
+  3  unknown day
+  4  input file not found
+  5  answers did not match
+  6  a new day could not be created
+  7  a day timed out
+  8  a request to the puzzle site failed
+  9  a submitted answer was not accepted
+  10 examples could not be extracted
+  11 a config file could not be read";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// Options shared by every day when it is run from the command line
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct RunOptions {
+    pub part: Option<Part>,
+    pub input: Option<PathBuf>,
+    pub input_dir: Option<PathBuf>,
+    pub format: Format,
+    /// How long a day may run before it is cancelled
+    pub timeout: Option<Duration>,
+    /// Show the timed phases of each day after its answers
+    pub phases: bool,
+}
+
+impl RunOptions {
+    // Whether the given part should be run. No `--part` means both.
+    pub fn wants(&self, part: Part) -> bool {
+        self.part.is_none_or(|wanted| wanted == part)
+    }
+    // `--input` names the file outright, otherwise it's looked up in the input directory
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.input
+            .clone()
+            .unwrap_or_else(|| InputResolver::new(self.input_dir.clone()).path(day, INPUT_FILE))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        day: u8,
+        options: RunOptions,
+    },
+    All {
+        options: RunOptions,
+        jobs: usize,
+    },
+    Verify {
+        options: RunOptions,
+        answers: Option<PathBuf>,
+    },
+    Bench {
+        day: u8,
+        options: RunOptions,
+        bench_options: BenchOptions,
+    },
+    Watch {
+        day: u8,
+        options: RunOptions,
+        interval: Duration,
+    },
+    New {
+        day: u8,
+    },
+    Fetch {
+        day: u8,
+        input_dir: Option<PathBuf>,
+        client: ClientOptions,
+    },
+    Submit {
+        day: u8,
+        part: Part,
+        options: RunOptions,
+        client: ClientOptions,
+    },
+    Extract {
+        day: u8,
+        page: PathBuf,
+        input_dir: Option<PathBuf>,
+    },
+    Record {
+        day: u8,
+        options: RunOptions,
+        year: u16,
+    },
+    Inspect {
+        day: u8,
+        options: RunOptions,
+    },
+    Help,
+    Interactive,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Everything that can stop a run, each with its own exit code
+#[derive(Debug)]
+pub enum Failure {
+    Usage(UsageError),
+    UnknownDay(String),
+    MissingInput(PathBuf, std::io::Error),
+    Solver(String),
+    Verification(String),
+    Scaffold(String),
+    Timeout,
+    Network(String),
+    Rejected(String),
+    Extract(String),
+    Config(String),
+}
+
+impl Failure {
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            Failure::Solver(_) => 1,
+            Failure::Usage(_) => 2,
+            Failure::UnknownDay(_) => 3,
+            Failure::MissingInput(..) => 4,
+            Failure::Verification(_) => 5,
+            Failure::Scaffold(_) => 6,
+            Failure::Timeout => 7,
+            Failure::Network(_) => 8,
+            Failure::Rejected(_) => 9,
+            Failure::Extract(_) => 10,
+            Failure::Config(_) => 11,
+        })
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Usage(error) => write!(f, "{error}\n\n{USAGE}"),
+            Failure::UnknownDay(day) => write!(f, "Unknown day: {day}"),
+            Failure::MissingInput(path, error) => {
+                write!(f, "Could not read input {}: {error}", path.display())
+            }
+            Failure::Solver(message) => write!(f, "Solver failed: {message}"),
+            Failure::Verification(message) => write!(f, "Verification failed: {message}"),
+            Failure::Scaffold(message) => write!(f, "Could not create day: {message}"),
+            Failure::Timeout => write!(f, "{TIMEOUT}"),
+            Failure::Network(message) => write!(f, "Request failed: {message}"),
+            Failure::Rejected(message) => write!(f, "Answer not accepted: {message}"),
+            Failure::Extract(message) => write!(f, "Could not extract examples: {message}"),
+            Failure::Config(message) => write!(f, "Invalid config: {message}"),
+        }
+    }
+}
+
+impl From<UsageError> for Failure {
+    fn from(error: UsageError) -> Self {
+        Failure::Usage(error)
+    }
+}
+
+/// Takes the logging flags out of the arguments, wherever they are, and returns the rest
+pub fn parse_log_args<I: IntoIterator<Item = String>>(
+    args: I,
+) -> Result<(LogOptions, Vec<String>), UsageError> {
+    let mut args = args.into_iter();
+    let mut log_options = LogOptions::default();
+    let (mut verbosity, mut quiet) = (0, false);
+    let mut rest = vec![];
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-v" | "--verbose" => verbosity += 1,
+            "-vv" => verbosity += 2,
+            "-q" | "--quiet" => quiet = true,
+            "--log-day" => {
+                for day in next_value(&mut args, &arg)?.split(',') {
+                    log_options.days.push(parse_day(day)?);
+                }
+            }
+            _ => rest.push(arg),
+        }
+    }
+    log_options.level = match (quiet, verbosity) {
+        (true, 0) => Level::Quiet,
+        (true, _) => return Err(UsageError("-q and -v can't be combined".to_owned())),
+        (false, 0) => Level::Warn,
+        (false, 1) => Level::Debug,
+        (false, _) => Level::Trace,
+    };
+    Ok((log_options, rest))
+}
+
+// Parses the arguments after the program name, with the built-in defaults
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, UsageError> {
+    parse_args_with(args, &Config::default())
+}
+
+/// Parses the arguments after the program name, starting from the defaults in the config
+pub fn parse_args_with<I: IntoIterator<Item = String>>(
+    args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut args = args.into_iter();
+    let command = match args.next() {
+        None => return Ok(Command::Interactive),
+        Some(command) => command,
+    };
+    match command.as_str() {
+        "-h" | "--help" | "help" => Ok(Command::Help),
+        "run" => parse_run(args, config),
+        "all" => parse_all(args, config),
+        "verify" => parse_verify(args, config),
+        "bench" => parse_bench(args, config),
+        "watch" => parse_watch(args, config),
+        "new" => parse_new(args),
+        "fetch" => parse_fetch(args, config),
+        "submit" => parse_submit(args, config),
+        "extract" => parse_extract(args, config),
+        "record" => parse_record(args, config),
+        "inspect" => parse_inspect(args, config),
+        _ => Err(UsageError(format!("Unknown command: {command}"))),
+    }
+}
+
+fn parse_run<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut options = config.run_options();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--part" | "-p" => options.part = Some(parse_part(&next_value(&mut args, &arg)?)?),
+            "--input" | "-i" => options.input = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--timeout" => options.timeout = Some(parse_timeout(&next_value(&mut args, &arg)?)?),
+            "--phases" => options.phases = true,
+            "--input-dir" => {
+                options.input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--format" | "-f" => options.format = parse_format(&next_value(&mut args, &arg)?)?,
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("Unknown option: {arg}")));
+            }
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    Ok(Command::Run { day, options })
+}
+
+fn parse_all<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut options = config.run_options();
+    let mut jobs = config.jobs();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--part" | "-p" => options.part = Some(parse_part(&next_value(&mut args, &arg)?)?),
+            "--timeout" => options.timeout = Some(parse_timeout(&next_value(&mut args, &arg)?)?),
+            "--phases" => options.phases = true,
+            "--input-dir" => {
+                options.input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--format" | "-f" => options.format = parse_format(&next_value(&mut args, &arg)?)?,
+            "--jobs" | "-j" => jobs = parse_count(&next_value(&mut args, &arg)?)?,
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    if jobs == 0 {
+        return Err(UsageError("--jobs must be at least 1".to_owned()));
+    }
+    Ok(Command::All { options, jobs })
+}
+
+fn parse_verify<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut options = config.run_options();
+    let mut answers = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--timeout" => options.timeout = Some(parse_timeout(&next_value(&mut args, &arg)?)?),
+            "--input-dir" => {
+                options.input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--answers" => answers = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    Ok(Command::Verify { options, answers })
+}
+
+fn parse_bench<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut options = config.run_options();
+    let mut bench_options = BenchOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--part" | "-p" => options.part = Some(parse_part(&next_value(&mut args, &arg)?)?),
+            "--input" | "-i" => options.input = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--input-dir" => {
+                options.input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--warmup" => bench_options.warmup = parse_count(&next_value(&mut args, &arg)?)?,
+            "--samples" => bench_options.samples = parse_count(&next_value(&mut args, &arg)?)?,
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("Unknown option: {arg}")));
+            }
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    if bench_options.samples == 0 {
+        return Err(UsageError("--samples must be at least 1".to_owned()));
+    }
+    Ok(Command::Bench {
+        day,
+        options,
+        bench_options,
+    })
+}
+
+fn parse_watch<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut options = config.run_options();
+    let mut interval = Duration::from_millis(500);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--part" | "-p" => options.part = Some(parse_part(&next_value(&mut args, &arg)?)?),
+            "--input" | "-i" => options.input = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--input-dir" => {
+                options.input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--interval" => {
+                interval =
+                    Duration::from_millis(parse_count(&next_value(&mut args, &arg)?)? as u64);
+            }
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("Unknown option: {arg}")));
+            }
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    if options.input.as_deref() == Some(Path::new(STDIN)) {
+        return Err(UsageError("stdin can't be watched".to_owned()));
+    }
+    Ok(Command::Watch {
+        day,
+        options,
+        interval,
+    })
+}
+
+// One worker per CPU, or a single one if that can't be determined
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, usize::from)
+}
+
+fn parse_new<I: Iterator<Item = String>>(args: I) -> Result<Command, UsageError> {
+    let mut day = None;
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    Ok(Command::New { day })
+}
+
+fn parse_fetch<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut input_dir = config.input_dir();
+    let mut client = config.client_options();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--input-dir" => input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--year" => client.year = parse_year(&next_value(&mut args, &arg)?)?,
+            "--session-file" => {
+                client.session_file = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--base-url" => client.base_url = next_value(&mut args, &arg)?,
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("Unknown option: {arg}")));
+            }
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    Ok(Command::Fetch {
+        day,
+        input_dir,
+        client,
+    })
+}
+
+fn parse_submit<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let (mut day, mut part) = (None, None);
+    let mut options = config.run_options();
+    let mut client = config.client_options();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--input" | "-i" => options.input = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--input-dir" => {
+                options.input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--year" => client.year = parse_year(&next_value(&mut args, &arg)?)?,
+            "--session-file" => {
+                client.session_file = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--base-url" => client.base_url = next_value(&mut args, &arg)?,
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("Unknown option: {arg}")));
+            }
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ if part.is_none() => part = Some(parse_part(&arg)?),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    let part = part.ok_or_else(|| UsageError("Missing part".to_owned()))?;
+    options.part = Some(part);
+    Ok(Command::Submit {
+        day,
+        part,
+        options,
+        client,
+    })
+}
+
+fn parse_extract<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let (mut day, mut page) = (None, None);
+    let mut input_dir = config.input_dir();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--input-dir" => input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("Unknown option: {arg}")));
+            }
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ if page.is_none() => page = Some(PathBuf::from(arg)),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    let page = page.ok_or_else(|| UsageError("Missing puzzle page".to_owned()))?;
+    Ok(Command::Extract {
+        day,
+        page,
+        input_dir,
+    })
+}
+
+fn parse_record<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut options = config.run_options();
+    let mut year = config.client_options().year;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--input-dir" => {
+                options.input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            "--year" => year = parse_year(&next_value(&mut args, &arg)?)?,
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("Unknown option: {arg}")));
+            }
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    Ok(Command::Record { day, options, year })
+}
+
+fn parse_inspect<I: Iterator<Item = String>>(
+    mut args: I,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut options = config.run_options();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--input" | "-i" => options.input = Some(PathBuf::from(next_value(&mut args, &arg)?)),
+            "--input-dir" => {
+                options.input_dir = Some(PathBuf::from(next_value(&mut args, &arg)?));
+            }
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("Unknown option: {arg}")));
+            }
+            _ if day.is_none() => day = Some(parse_day(&arg)?),
+            _ => return Err(UsageError(format!("Unexpected argument: {arg}"))),
+        }
+    }
+    let day = day.ok_or_else(|| UsageError("Missing day".to_owned()))?;
+    Ok(Command::Inspect { day, options })
+}
+
+pub fn parse_year(value: &str) -> Result<u16, UsageError> {
+    value
+        .parse()
+        .ok()
+        .filter(|year| *year >= 2015)
+        .ok_or_else(|| UsageError(format!("Expected a year from 2015 on, got {value}")))
+}
+
+pub fn parse_count(value: &str) -> Result<usize, UsageError> {
+    value
+        .parse()
+        .map_err(|_| UsageError(format!("Expected a count, got {value}")))
+}
+
+pub fn parse_timeout(value: &str) -> Result<Duration, UsageError> {
+    match parse_count(value)? {
+        0 => Err(UsageError("--timeout must be at least 1".to_owned())),
+        millis => Ok(Duration::from_millis(millis as u64)),
+    }
+}
+
+fn next_value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, UsageError> {
+    args.next()
+        .ok_or_else(|| UsageError(format!("{flag} requires a value")))
+}
+
+pub fn parse_day(value: &str) -> Result<u8, UsageError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| UsageError(format!("Day must be a number, got {value}")))
+}
+
+pub fn parse_format(value: &str) -> Result<Format, UsageError> {
+    match value {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(UsageError(format!(
+            "Format must be text, json or csv, got {value}"
+        ))),
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, UsageError> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(UsageError(format!("Part must be 1 or 2, got {value}"))),
+    }
+}
+
+#[cfg(test)]
+mod test_parse_args {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_no_args() {
+        assert_eq!(parse_args(args(&[])), Ok(Command::Interactive));
+    }
+
+    #[test]
+    fn test_help() {
+        assert_eq!(parse_args(args(&["--help"])), Ok(Command::Help));
+        assert_eq!(parse_args(args(&["run", "-h"])), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!(
+            parse_args(args(&["run", "6"])),
+            Ok(Command::Run {
+                day: 6,
+                options: RunOptions::default()
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["run", "--part", "2", "7", "--input", "other.txt"])),
+            Ok(Command::Run {
+                day: 7,
+                options: RunOptions {
+                    part: Some(Part::Two),
+                    input: Some(PathBuf::from("other.txt")),
+                    input_dir: None,
+                    format: Format::Text,
+                    timeout: None,
+                    phases: false,
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let config = Config {
+            year: Some(2023),
+            format: Some(Format::Csv),
+            jobs: Some(3),
+            timeout: Some(Duration::from_millis(2000)),
+            session_file: Some(PathBuf::from("/secrets/session")),
+            ..Config::default()
+        };
+        assert_eq!(
+            parse_args_with(args(&["all", "--format", "json"]), &config),
+            Ok(Command::All {
+                options: RunOptions {
+                    format: Format::Json,
+                    timeout: Some(Duration::from_millis(2000)),
+                    ..RunOptions::default()
+                },
+                jobs: 3
+            })
+        );
+        let Ok(Command::Fetch { client, .. }) =
+            parse_args_with(args(&["fetch", "6", "--year", "2024"]), &config)
+        else {
+            panic!("expected fetch");
+        };
+        assert_eq!(client.year, 2024);
+        assert_eq!(client.session_file, Some(PathBuf::from("/secrets/session")));
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(
+            parse_args(args(&["all"])),
+            Ok(Command::All {
+                options: RunOptions::default(),
+                jobs: default_jobs()
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["all", "--input-dir", "inputs"])),
+            Ok(Command::All {
+                options: RunOptions {
+                    input_dir: Some(PathBuf::from("inputs")),
+                    ..RunOptions::default()
+                },
+                jobs: default_jobs()
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["all", "--format", "json"])),
+            Ok(Command::All {
+                options: RunOptions {
+                    format: Format::Json,
+                    ..RunOptions::default()
+                },
+                jobs: default_jobs()
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["all", "--jobs", "3"])),
+            Ok(Command::All {
+                options: RunOptions::default(),
+                jobs: 3
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["all", "--timeout", "1500"])),
+            Ok(Command::All {
+                options: RunOptions {
+                    timeout: Some(Duration::from_millis(1500)),
+                    ..RunOptions::default()
+                },
+                jobs: default_jobs()
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["all", "--phases"])),
+            Ok(Command::All {
+                options: RunOptions {
+                    phases: true,
+                    ..RunOptions::default()
+                },
+                jobs: default_jobs()
+            })
+        );
+        assert!(parse_args(args(&["all", "--timeout", "0"])).is_err());
+        assert!(parse_args(args(&["all", "-j", "0"])).is_err());
+        assert!(parse_args(args(&["all", "--format", "xml"])).is_err());
+        assert!(parse_args(args(&["all", "--input", "input.txt"])).is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(
+            parse_args(args(&["verify", "--answers", "answers.txt"])),
+            Ok(Command::Verify {
+                options: RunOptions::default(),
+                answers: Some(PathBuf::from("answers.txt"))
+            })
+        );
+        assert!(parse_args(args(&["verify", "--part", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_bench() {
+        assert_eq!(
+            parse_args(args(&["bench", "6", "--samples", "5", "--warmup", "0"])),
+            Ok(Command::Bench {
+                day: 6,
+                options: RunOptions::default(),
+                bench_options: BenchOptions {
+                    warmup: 0,
+                    samples: 5
+                }
+            })
+        );
+        assert!(parse_args(args(&["bench", "6", "--samples", "0"])).is_err());
+        assert!(parse_args(args(&["bench", "6", "--warmup", "-1"])).is_err());
+    }
+
+    #[test]
+    fn test_watch() {
+        assert_eq!(
+            parse_args(args(&["watch", "6", "--interval", "100"])),
+            Ok(Command::Watch {
+                day: 6,
+                options: RunOptions::default(),
+                interval: Duration::from_millis(100)
+            })
+        );
+        assert!(parse_args(args(&["watch"])).is_err());
+        assert!(parse_args(args(&["watch", "6", "--input", "-"])).is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(parse_args(args(&["new", "9"])), Ok(Command::New { day: 9 }));
+        assert!(parse_args(args(&["new"])).is_err());
+        assert!(parse_args(args(&["new", "9", "10"])).is_err());
+    }
+
+    #[test]
+    fn test_fetch() {
+        assert_eq!(
+            parse_args(args(&["fetch", "3"])),
+            Ok(Command::Fetch {
+                day: 3,
+                input_dir: None,
+                client: ClientOptions::default()
+            })
+        );
+        assert_eq!(
+            parse_args(args(&[
+                "fetch",
+                "3",
+                "--year",
+                "2023",
+                "--base-url",
+                "http://127.0.0.1:8000"
+            ])),
+            Ok(Command::Fetch {
+                day: 3,
+                input_dir: None,
+                client: ClientOptions {
+                    year: 2023,
+                    base_url: "http://127.0.0.1:8000".to_owned(),
+                    session_file: None,
+                    contact: None,
+                }
+            })
+        );
+        assert!(parse_args(args(&["fetch", "3", "--year", "24"])).is_err());
+    }
+
+    #[test]
+    fn test_submit() {
+        assert_eq!(
+            parse_args(args(&[
+                "submit",
+                "6",
+                "2",
+                "--base-url",
+                "http://localhost:8000"
+            ])),
+            Ok(Command::Submit {
+                day: 6,
+                part: Part::Two,
+                options: RunOptions {
+                    part: Some(Part::Two),
+                    ..RunOptions::default()
+                },
+                client: ClientOptions {
+                    base_url: "http://localhost:8000".to_owned(),
+                    ..ClientOptions::default()
+                }
+            })
+        );
+        assert!(parse_args(args(&["submit", "6"])).is_err());
+        assert!(parse_args(args(&["submit", "6", "3"])).is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        assert_eq!(
+            parse_args(args(&["extract", "6", "day6.html"])),
+            Ok(Command::Extract {
+                day: 6,
+                page: PathBuf::from("day6.html"),
+                input_dir: None
+            })
+        );
+        assert!(parse_args(args(&["extract", "6"])).is_err());
+    }
+
+    #[test]
+    fn test_record() {
+        assert_eq!(
+            parse_args(args(&["record", "6", "--year", "2023"])),
+            Ok(Command::Record {
+                day: 6,
+                options: RunOptions::default(),
+                year: 2023
+            })
+        );
+        assert!(parse_args(args(&["record"])).is_err());
+    }
+
+    #[test]
+    fn test_inspect() {
+        assert_eq!(
+            parse_args(args(&["inspect", "5", "-i", "big.txt"])),
+            Ok(Command::Inspect {
+                day: 5,
+                options: RunOptions {
+                    input: Some(PathBuf::from("big.txt")),
+                    ..RunOptions::default()
+                }
+            })
+        );
+        assert!(parse_args(args(&["inspect"])).is_err());
+    }
+
+    #[test]
+    fn test_log_args() {
+        assert_eq!(
+            parse_log_args(args(&["run", "-vv", "6", "--log-day", "6,7"])),
+            Ok((
+                LogOptions {
+                    level: Level::Trace,
+                    days: vec![6, 7]
+                },
+                args(&["run", "6"])
+            ))
+        );
+        assert_eq!(
+            parse_log_args(args(&["-q", "all"])).unwrap().0.level,
+            Level::Quiet
+        );
+        assert_eq!(
+            parse_log_args(args(&["all", "-v"])).unwrap().0.level,
+            Level::Debug
+        );
+        assert!(parse_log_args(args(&["all", "-q", "-v"])).is_err());
+        assert!(parse_log_args(args(&["all", "--log-day", "x"])).is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse_args(args(&["walk", "6"])).is_err());
+        assert!(parse_args(args(&["run"])).is_err());
+        assert!(parse_args(args(&["run", "six"])).is_err());
+        assert!(parse_args(args(&["run", "6", "--part", "3"])).is_err());
+        assert!(parse_args(args(&["run", "6", "--input"])).is_err());
+        assert!(parse_args(args(&["run", "6", "--fast"])).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::cli::Failure;
+use crate::http::{self, Response};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+/// Environment variable holding the session cookie, read before the session file
+pub const SESSION_ENV: &str = "AOC_SESSION";
+pub const DEFAULT_YEAR: u16 = 2024;
+/// Names the tool in the User-Agent, which `user_agent` completes with how to reach its user
+pub const USER_AGENT: &str = concat!("AdventOfCode2024-aoc/", env!("CARGO_PKG_VERSION"));
+/// The least time between two requests, across runs of the binary
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where and as whom to talk to the puzzle site
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ClientOptions {
+    pub year: u16,
+    pub base_url: String,
+    pub session_file: Option<PathBuf>,
+    /// An email address or URL for the site's admins to reach whoever runs the tool
+    pub contact: Option<String>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        ClientOptions {
+            year: DEFAULT_YEAR,
+            base_url: BASE_URL.to_owned(),
+            session_file: None,
+            contact: None,
+        }
+    }
+}
+
+/// Talks to the puzzle site with the session cookie, leaving at least `min_interval` between
+/// requests. The time of the last request is kept in a stamp file so that separate runs are
+/// throttled too.
+#[derive(Debug)]
+pub struct Client {
+    base_url: String,
+    session: String,
+    user_agent: String,
+    min_interval: Duration,
+    stamp: Option<PathBuf>,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: String) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session,
+            user_agent: user_agent(None),
+            min_interval: MIN_INTERVAL,
+            stamp: cache_dir().map(|dir| dir.join("last-request")),
+        }
+    }
+
+    // The client described by the options, with the session token from the environment or file
+    pub fn from_options(options: &ClientOptions) -> Result<Self, Failure> {
+        if options.contact.is_none() {
+            crate::log::warning!(
+                "no contact set in {}; the site asks automated tools to say how to reach their user",
+                crate::config::CONFIG_FILE
+            );
+        }
+        Ok(Client::new(
+            &options.base_url,
+            session_token(options.session_file.as_deref())?,
+        )
+        .contact(options.contact.as_deref()))
+    }
+
+    pub fn contact(mut self, contact: Option<&str>) -> Self {
+        self.user_agent = user_agent(contact);
+        self
+    }
+
+    #[cfg(test)]
+    pub fn throttle(mut self, min_interval: Duration, stamp: Option<PathBuf>) -> Self {
+        self.min_interval = min_interval;
+        self.stamp = stamp;
+        self
+    }
+
+    pub fn get(&self, path: &str) -> Result<Response, Failure> {
+        self.send("GET", path, None)
+    }
+
+    pub fn post(&self, path: &str, form: &str) -> Result<Response, Failure> {
+        self.send("POST", path, Some(form))
+    }
+
+    fn send(&self, method: &str, path: &str, body: Option<&str>) -> Result<Response, Failure> {
+        self.wait_turn();
+        let url = format!("{}{path}", self.base_url);
+        let cookie = format!("session={}", self.session);
+        let mut headers = vec![
+            ("User-Agent", self.user_agent.as_str()),
+            ("Cookie", cookie.as_str()),
+        ];
+        if body.is_some() {
+            headers.push(("Content-Type", "application/x-www-form-urlencoded"));
+        }
+        let response = http::send(method, &url, &headers, body)
+            .map_err(|error| Failure::Network(format!("{url}: {error}")));
+        if let Some(stamp) = &self.stamp {
+            // Throttling is a courtesy; a stamp that can't be written isn't worth failing over
+            let _ = fs::create_dir_all(stamp.parent().unwrap_or(Path::new(".")))
+                .and_then(|()| fs::write(stamp, ""));
+        }
+        response
+    }
+
+    // Sleeps until `min_interval` has passed since the last request recorded in the stamp
+    fn wait_turn(&self) {
+        let Some(stamp) = &self.stamp else {
+            return;
+        };
+        let since = fs::metadata(stamp)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+        if let Some(remaining) = since.and_then(|since| self.min_interval.checked_sub(since)) {
+            crate::log::warning!("waiting {}s between requests", remaining.as_secs() + 1);
+            thread::sleep(remaining);
+        }
+    }
+}
+
+/// The tool's name and version, then how to reach its user when known and what the tool does
+pub fn user_agent(contact: Option<&str>) -> String {
+    let purpose = "personal puzzle runner; caches inputs and throttles requests";
+    match contact {
+        Some(contact) => format!("{USER_AGENT} (+{contact}; {purpose})"),
+        None => format!("{USER_AGENT} ({purpose})"),
+    }
+}
+
+// `$XDG_CACHE_HOME/aoc`, falling back on `~/.cache/aoc`
+fn cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .map(|dir| dir.join("aoc"))
+}
+
+// `~/.config/aoc/session`
+fn default_session_file() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| Path::new(&home).join(".config/aoc/session"))
+}
+
+/// The session cookie from `$AOC_SESSION`, or else from the given file or `~/.config/aoc/session`
+pub fn session_token(file: Option<&Path>) -> Result<String, Failure> {
+    if let Some(token) = env::var(SESSION_ENV)
+        .ok()
+        .and_then(|token| non_empty(&token))
+    {
+        return Ok(token);
+    }
+    let path = file
+        .map(Path::to_owned)
+        .or_else(default_session_file)
+        .ok_or_else(|| Failure::Network(format!("no session token: set ${SESSION_ENV}")))?;
+    let token = fs::read_to_string(&path).map_err(|error| {
+        Failure::Network(format!(
+            "no session token in {} ({error}); set ${SESSION_ENV} or save the session cookie there",
+            path.display()
+        ))
+    })?;
+    non_empty(&token).ok_or_else(|| {
+        Failure::Network(format!(
+            "{} is empty; set ${SESSION_ENV} or save the session cookie there",
+            path.display()
+        ))
+    })
+}
+
+// The token without the whitespace around it, unless that leaves nothing
+fn non_empty(token: &str) -> Option<String> {
+    Some(token.trim().to_owned()).filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod test_client {
+    use super::*;
+
+    #[test]
+    fn test_user_agent() {
+        assert_eq!(
+            user_agent(Some("me@example.com")),
+            format!(
+                "{USER_AGENT} (+me@example.com; personal puzzle runner; caches inputs and throttles \
+                 requests)"
+            )
+        );
+        assert!(!user_agent(None).contains('+'));
+    }
+
+    #[test]
+    fn test_non_empty() {
+        assert_eq!(non_empty(" abc\n"), Some("abc".to_owned()));
+        assert_eq!(non_empty(" \n"), None);
+    }
+}
@@ -0,0 +1,292 @@
+//! What each command does once its arguments are parsed. Results go to stdout, and anything that
+//! goes wrong comes back as a `Failure` for the binary to turn into an exit code.
+
+use crate::cli::{self, Command, Failure, Part, RunOptions};
+use crate::client::{Client, ClientOptions};
+use crate::config::Config;
+use crate::extract;
+use crate::fetch::{self, Fetched};
+use crate::input::{InputResolver, INPUT_FILE};
+use crate::inspect;
+use crate::output::{self, Format};
+use crate::submit::{self, Submissions, Verdict};
+use crate::verify::{self, ExpectedAnswers, Status};
+use crate::{bench, phase, record, repl, runner, scaffold, solution, watch, SOLUTIONS};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Carries out a parsed command, with `config` supplying what the interactive session needs
+pub fn execute(command: Command, config: &Config) -> Result<(), Failure> {
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Command::Run { day, options } => run_day(day, &options),
+        Command::All { options, jobs } => run_all(&options, jobs),
+        Command::Verify { options, answers } => run_verify(&options, answers),
+        Command::Bench {
+            day,
+            options,
+            bench_options,
+        } => {
+            let results = bench::bench(find_day(day)?, &options, &bench_options)?;
+            println!("{}", bench::format_results(&results));
+            Ok(())
+        }
+        Command::Watch {
+            day,
+            options,
+            interval,
+        } => watch::watch(find_day(day)?, &options, interval),
+        Command::New { day } => new_day(day),
+        Command::Fetch {
+            day,
+            input_dir,
+            client,
+        } => fetch_input(day, input_dir, &client),
+        Command::Submit {
+            day,
+            part,
+            options,
+            client,
+        } => submit_answer(day, part, &options, &client),
+        Command::Extract {
+            day,
+            page,
+            input_dir,
+        } => extract_examples(day, &page, input_dir),
+        Command::Record { day, options, year } => record_answers(day, &options, year),
+        Command::Inspect { day, options } => inspect_input(day, &options),
+        Command::Interactive => interactive(config),
+    }
+}
+
+fn interactive(config: &Config) -> Result<(), Failure> {
+    let session = repl::Session::new(SOLUTIONS, InputResolver::new(config.input_dir()));
+    repl::run(session, io::stdin().lock(), io::stdout())
+        .map_err(|error| Failure::Solver(error.to_string()))
+}
+
+fn submit_answer(
+    day: u8,
+    part: Part,
+    options: &RunOptions,
+    client_options: &ClientOptions,
+) -> Result<(), Failure> {
+    let report = runner::run(find_day(day)?, options);
+    report.parse?;
+    let part_report = match part {
+        Part::One => report.part1,
+        Part::Two => report.part2,
+    };
+    let answer = part_report
+        .expect("the part was asked for")
+        .answer
+        .map_err(Failure::Solver)?;
+    let client = Client::from_options(client_options)?;
+    let submissions = InputResolver::new(options.input_dir.clone()).submissions_path();
+    let answer = answer.to_string();
+    let verdict = submit::submit(
+        &client,
+        &submissions,
+        client_options.year,
+        day,
+        part,
+        &answer,
+    )?;
+    match verdict {
+        Verdict::Correct | Verdict::AlreadySolved => {
+            println!("Day {day} part {}: {answer} is {verdict}", part.number());
+            println!("Run aoc record {day} to keep a digest of it in the expected answers");
+            Ok(())
+        }
+        _ => Err(Failure::Rejected(format!("{answer} is {verdict}"))),
+    }
+}
+
+fn new_day(day: u8) -> Result<(), Failure> {
+    let src = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src");
+    for path in scaffold::scaffold(&src, day)? {
+        println!("Wrote {}", path.display());
+    }
+    println!("Rebuild to run day {day}");
+    Ok(())
+}
+
+fn fetch_input(
+    day: u8,
+    input_dir: Option<PathBuf>,
+    client_options: &ClientOptions,
+) -> Result<(), Failure> {
+    let path = InputResolver::new(input_dir).path(day, INPUT_FILE);
+    let client = Client::from_options(client_options)?;
+    match fetch::fetch(&client, client_options.year, day, &path)? {
+        Fetched::Cached(path) => println!("Already have {}", path.display()),
+        Fetched::Downloaded(path) => println!("Wrote {}", path.display()),
+    }
+    Ok(())
+}
+
+fn extract_examples(day: u8, page: &Path, input_dir: Option<PathBuf>) -> Result<(), Failure> {
+    let contents = fs::read_to_string(page)
+        .map_err(|error| Failure::Extract(format!("{}: {error}", page.display())))?;
+    let extracted = extract::extract(&contents);
+    if extracted.examples.is_empty() {
+        return Err(Failure::Extract(format!(
+            "no examples in {}",
+            page.display()
+        )));
+    }
+    let resolver = InputResolver::new(input_dir);
+    let written = extract::write(
+        &extracted,
+        day,
+        &resolver.path(day, ""),
+        &resolver.answers_path(),
+    )?;
+    for path in &written {
+        println!("Wrote {}", path.display());
+    }
+    if written.is_empty() {
+        println!("Nothing new in {}", page.display());
+    }
+    Ok(())
+}
+
+fn record_answers(day: u8, options: &RunOptions, year: u16) -> Result<(), Failure> {
+    let resolver = InputResolver::new(options.input_dir.clone());
+    let submissions = Submissions::load(&resolver.submissions_path()).map_err(|error| {
+        Failure::Verification(format!(
+            "{}: {error}",
+            resolver.submissions_path().display()
+        ))
+    })?;
+    let recorded = record::record(
+        find_day(day)?,
+        options,
+        year,
+        &submissions,
+        &resolver.answers_path(),
+    )?;
+    for (part, outcome) in recorded {
+        println!("Day {day} part {}: {outcome}", part.number());
+    }
+    Ok(())
+}
+
+// Any anomaly fails the command, so that scripts can check inputs
+fn inspect_input(day: u8, options: &RunOptions) -> Result<(), Failure> {
+    let path = options.input_path(day);
+    let inspection = inspect::inspect(find_day(day)?, &path)?;
+    println!("{inspection}");
+    match inspection.anomalies.len() {
+        0 => Ok(()),
+        count => Err(Failure::Verification(format!(
+            "{count} anomalies in {}",
+            path.display()
+        ))),
+    }
+}
+
+fn find_day(day: u8) -> Result<&'static dyn solution::DynSolution, Failure> {
+    solution::find(SOLUTIONS, day).ok_or_else(|| Failure::UnknownDay(day.to_string()))
+}
+
+fn run_day(day: u8, options: &RunOptions) -> Result<(), Failure> {
+    let report = runner::run(find_day(day)?, options);
+    if options.format == Format::Text {
+        for (name, part) in [("Part1", &report.part1), ("Part2", &report.part2)] {
+            if let Some(runner::PartReport {
+                answer: Ok(answer), ..
+            }) = part
+            {
+                println!("{name}: {answer}");
+            }
+        }
+        if options.phases {
+            println!("{}", format_phases(&report.phases));
+        }
+        report.into_result()
+    } else {
+        let reports = [(day, report)];
+        print_records(options.format, &reports);
+        let [(_, report)] = reports;
+        report.into_result()
+    }
+}
+
+// Runs every day even if some of them fail, then reports the first failure
+fn run_all(options: &RunOptions, jobs: usize) -> Result<(), Failure> {
+    let reports = runner::run_all(SOLUTIONS, options, jobs);
+    if options.format == Format::Text {
+        println!("{}", runner::format_table(&reports));
+        if options.phases {
+            for (day, report) in &reports {
+                println!("\nDay {day}");
+                for line in format_phases(&report.phases).lines() {
+                    println!("  {line}");
+                }
+            }
+        }
+    } else {
+        print_records(options.format, &reports);
+    }
+    reports
+        .into_iter()
+        .try_for_each(|(_, report)| report.into_result())
+}
+
+// The phase tree, with a note when the input was read more than once
+fn format_phases(phases: &[phase::Phase]) -> String {
+    let mut text = phase::format_tree(phases);
+    let reads = phase::count(phases, phase::IO);
+    if reads > 1 {
+        text.push_str(&format!("\nread its input {reads} times"));
+    }
+    text
+}
+
+fn print_records(format: Format, reports: &[(u8, runner::DayReport)]) {
+    let records = output::records(reports);
+    match format {
+        Format::Json => println!("{}", output::format_json(&records)),
+        Format::Csv => println!("{}", output::format_csv(&records)),
+        Format::Text => unreachable!("text output is printed by each command"),
+    }
+}
+
+fn run_verify(options: &RunOptions, answers: Option<PathBuf>) -> Result<(), Failure> {
+    let resolver = InputResolver::new(options.input_dir.clone());
+    let answers = answers.unwrap_or_else(|| resolver.answers_path());
+    let contents = fs::read_to_string(&answers)
+        .map_err(|error| Failure::MissingInput(answers.clone(), error))?;
+    let expected = ExpectedAnswers::parse(&contents)
+        .map_err(|error| Failure::Verification(format!("{}: {error}", answers.display())))?;
+
+    let checks = verify::verify(SOLUTIONS, &resolver, &expected);
+    for check in checks.iter() {
+        println!("{check}");
+    }
+    let count = |status: Status| {
+        checks
+            .iter()
+            .filter(|check| check.status() == status)
+            .count()
+    };
+    let failed = count(Status::Fail);
+    println!(
+        "{} passed, {failed} failed, {} missing",
+        count(Status::Pass),
+        count(Status::Missing)
+    );
+    if failed > 0 {
+        Err(Failure::Verification(format!(
+            "{failed} of {} checks failed",
+            checks.len()
+        )))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+use crate::cli::{self, Failure, RunOptions};
+use crate::client::ClientOptions;
+use crate::input::INPUT_DIR_ENV;
+use crate::output::Format;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Defaults for the command line from `aoc.toml`. Whatever a file leaves out keeps its built-in
+/// default, and flags override all of it.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct Config {
+    pub year: Option<u16>,
+    pub input_dir: Option<PathBuf>,
+    pub format: Option<Format>,
+    pub timeout: Option<Duration>,
+    /// How many days `all` runs at once
+    pub jobs: Option<usize>,
+    pub session_file: Option<PathBuf>,
+    /// Sent in the User-Agent so that the site can reach whoever runs the tool
+    pub contact: Option<String>,
+}
+
+impl Config {
+    /// Reads the file in the repository root, then the one in the user's config directory, which
+    /// wins where both set a value. Neither has to exist.
+    pub fn load() -> Result<Self, Failure> {
+        let mut config = Config::default();
+        for path in [PathBuf::from(env!("CARGO_MANIFEST_DIR"))]
+            .into_iter()
+            .chain(user_config_dir())
+            .map(|dir| dir.join(CONFIG_FILE))
+        {
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(Failure::Config(format!("{}: {error}", path.display()))),
+            };
+            let base = path.parent().unwrap_or(Path::new("."));
+            let file = Config::parse(&contents, base)
+                .map_err(|error| Failure::Config(format!("{}: {error}", path.display())))?;
+            config = config.overridden_by(file);
+        }
+        Ok(config)
+    }
+
+    /// Parses `key = value` lines, with `#` comments, quoted strings and plain integers: the
+    /// little of TOML a flat file of settings needs. Relative paths are taken from `base`.
+    pub fn parse(contents: &str, base: &Path) -> Result<Self, String> {
+        let mut config = Config::default();
+        for (index, line) in contents.lines().enumerate() {
+            let at_line = |message: String| format!("line {}: {message}", index + 1);
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| at_line(format!("expected key = value, got {line}")))?;
+            let (key, value) = (key.trim(), value.trim());
+            let string =
+                || parse_string(value).ok_or_else(|| at_line(format!("{key} must be a string")));
+            let integer = || {
+                value
+                    .replace('_', "")
+                    .parse::<u64>()
+                    .map_err(|_| at_line(format!("{key} must be a whole number")))
+            };
+            let path = |value: String| base.join(expand_home(&value));
+            match key {
+                "year" => {
+                    config.year = Some(
+                        cli::parse_year(&integer()?.to_string())
+                            .map_err(|error| at_line(error.0))?,
+                    )
+                }
+                "input_dir" => config.input_dir = Some(path(string()?)),
+                "format" => {
+                    config.format =
+                        Some(cli::parse_format(&string()?).map_err(|error| at_line(error.0))?)
+                }
+                "timeout" => {
+                    config.timeout = Some(
+                        cli::parse_timeout(&integer()?.to_string())
+                            .map_err(|error| at_line(error.0))?,
+                    )
+                }
+                "jobs" => match integer()? {
+                    0 => return Err(at_line("jobs must be at least 1".to_owned())),
+                    jobs => config.jobs = Some(jobs as usize),
+                },
+                "session_file" => config.session_file = Some(path(string()?)),
+                "contact" => config.contact = Some(string()?),
+                _ => return Err(at_line(format!("unknown setting {key}"))),
+            }
+        }
+        Ok(config)
+    }
+
+    // Every value set in `other` replaces the one here
+    fn overridden_by(self, other: Config) -> Config {
+        Config {
+            year: other.year.or(self.year),
+            input_dir: other.input_dir.or(self.input_dir),
+            format: other.format.or(self.format),
+            timeout: other.timeout.or(self.timeout),
+            jobs: other.jobs.or(self.jobs),
+            session_file: other.session_file.or(self.session_file),
+            contact: other.contact.or(self.contact),
+        }
+    }
+
+    /// The input directory to use when no `--input-dir` is given. `$AOC_INPUT_DIR` still beats the
+    /// file, as it is the more local of the two.
+    pub fn input_dir(&self) -> Option<PathBuf> {
+        self.input_dir
+            .clone()
+            .filter(|_| env::var_os(INPUT_DIR_ENV).is_none())
+    }
+
+    /// The options a run starts from before its flags are applied
+    pub fn run_options(&self) -> RunOptions {
+        RunOptions {
+            input_dir: self.input_dir(),
+            format: self.format.unwrap_or_default(),
+            timeout: self.timeout,
+            ..RunOptions::default()
+        }
+    }
+
+    pub fn client_options(&self) -> ClientOptions {
+        let defaults = ClientOptions::default();
+        ClientOptions {
+            year: self.year.unwrap_or(defaults.year),
+            session_file: self.session_file.clone(),
+            contact: self.contact.clone(),
+            ..defaults
+        }
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs.unwrap_or_else(cli::default_jobs)
+    }
+}
+
+// Everything from a `#` that isn't inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('"'), '\\') => escaped = true,
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '#') => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+// A basic string in double quotes, with the common escapes, or a literal one in single quotes
+fn parse_string(value: &str) -> Option<String> {
+    if let Some(literal) = value.strip_prefix('\'') {
+        return literal.strip_suffix('\'').map(str::to_owned);
+    }
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut string = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        string.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                '"' => '"',
+                't' => '\t',
+                'n' => '\n',
+                _ => return None,
+            },
+            _ => c,
+        });
+    }
+    Some(string)
+}
+
+// `~/…` is relative to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+// `$XDG_CONFIG_HOME/aoc`, falling back on `~/.config/aoc`
+fn user_config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|dir| dir.join("aoc"))
+}
+
+#[cfg(test)]
+mod test_config {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            "# shared settings\n\
+             year = 2023\n\
+             input_dir = \"inputs\"  # next to this file\n\
+             format = 'json'\n\
+             timeout = 1_500\n\
+             jobs = 4\n\
+             session_file = \"/secrets/aoc#session\"\n\
+             contact = \"me@example.com\"\n",
+            Path::new("/repo"),
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                year: Some(2023),
+                input_dir: Some(PathBuf::from("/repo/inputs")),
+                format: Some(Format::Json),
+                timeout: Some(Duration::from_millis(1500)),
+                jobs: Some(4),
+                session_file: Some(PathBuf::from("/secrets/aoc#session")),
+                contact: Some("me@example.com".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |contents| Config::parse(contents, Path::new("/repo")).unwrap_err();
+        assert_eq!(parse("\nyears = 2023"), "line 2: unknown setting years");
+        assert_eq!(parse("format = xml"), "line 1: format must be a string");
+        assert_eq!(
+            parse("format = \"xml\""),
+            "line 1: Format must be text, json or csv, got xml"
+        );
+        assert_eq!(parse("jobs = 0"), "line 1: jobs must be at least 1");
+        assert_eq!(
+            parse("timeout"),
+            "line 1: expected key = value, got timeout"
+        );
+    }
+
+    #[test]
+    fn test_overridden_by() {
+        let repo = Config {
+            year: Some(2023),
+            jobs: Some(2),
+            ..Config::default()
+        };
+        let user = Config {
+            jobs: Some(8),
+            ..Config::default()
+        };
+        let config = repo.overridden_by(user);
+        assert_eq!((config.year, config.jobs), (Some(2023), Some(8)));
+        assert_eq!(config.client_options().year, 2023);
+        assert_eq!(config.jobs(), 8);
+    }
+}
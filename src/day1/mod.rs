@@ -1,25 +1,42 @@
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
+use crate::error::{self, Error};
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
 
-pub fn day1() -> io::Result<()> {
-    let file = File::open("./src/day1/input.txt")?;
-    let reader = BufReader::new(file);
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = (Vec<u32>, Vec<u32>);
+    const DAY: u8 = 1;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+        parse(reader)
+    }
+    fn part1(&self, (left_list, right_list): &Self::Input) -> error::Result<Answer> {
+        Ok(calc_total_distance(left_list, right_list).into())
+    }
+    fn part2(&self, (left_list, right_list): &Self::Input) -> error::Result<Answer> {
+        Ok(calc_similarity_score(left_list, right_list).into())
+    }
+}
+
+fn parse<R: BufRead>(reader: R) -> error::Result<(Vec<u32>, Vec<u32>)> {
     let mut left_list: Vec<u32> = vec![];
     let mut right_list: Vec<u32> = vec![];
 
-    for line in reader.lines().map_while(Result::ok) {
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
         let mut split = line.split_whitespace();
-        left_list.push(split.next().unwrap().parse().unwrap());
-        right_list.push(split.next().unwrap().parse().unwrap());
+        let mut next = || {
+            let field = split
+                .next()
+                .ok_or_else(|| Error::parse(index + 1, line.len() + 1, "expected two numbers"))?;
+            error::number(index + 1, &line, field)
+        };
+        left_list.push(next()?);
+        right_list.push(next()?);
     }
 
-    let total_distance = calc_total_distance(&left_list, &right_list);
-    let similarity_score = calc_similarity_score(&left_list, &right_list);
-
-    println!("total distance: {}", total_distance);
-    println!("similarity score: {}", similarity_score);
-
-    Ok(())
+    Ok((left_list, right_list))
 }
 
 fn calc_total_distance(left_list: &[u32], right_list: &[u32]) -> u32 {
@@ -43,10 +60,26 @@ fn calc_similarity_score(left_list: &[u32], right_list: &[u32]) -> u32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("3   4\n4   3\n".as_bytes()).unwrap(),
+            (vec![3, 4], vec![4, 3])
+        );
+        assert_eq!(
+            parse("3   4\n4   x".as_bytes()).unwrap_err().to_string(),
+            "2:5: expected a number, got \"x\""
+        );
+        assert_eq!(
+            parse("3".as_bytes()).unwrap_err().to_string(),
+            "1:2: expected two numbers"
+        );
+    }
+
     #[test]
     fn test_distance() {
         assert_eq!(
-            calc_total_distance(&vec![3, 4, 2, 1, 3, 3], &vec![4, 3, 5, 3, 9, 3]),
+            calc_total_distance(&[3, 4, 2, 1, 3, 3], &[4, 3, 5, 3, 9, 3]),
             11
         );
     }
@@ -60,7 +93,7 @@ mod tests {
     #[test]
     fn test_similarity_scores() {
         assert_eq!(
-            calc_similarity_score(&vec![3, 4, 2, 1, 3, 3], &vec![4, 3, 5, 3, 9, 3]),
+            calc_similarity_score(&[3, 4, 2, 1, 3, 3], &[4, 3, 5, 3, 9, 3]),
             31
         );
     }
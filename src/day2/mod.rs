@@ -1,29 +1,46 @@
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
+use crate::cancel;
+use crate::error;
+use crate::log;
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
 
 static MAX_SAFE_DIFFERENCE: u8 = 3;
 static MIN_SAFE_DIFFERENCE: u8 = 1;
 
-pub fn day2() -> io::Result<()> {
-    let file = File::open("./src/day2/input.txt")?;
-    let reader = BufReader::new(file);
+pub struct Day2;
 
-    let data = reader
+impl Solution for Day2 {
+    type Input = Vec<Vec<u32>>;
+    const DAY: u8 = 2;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+        parse(reader)
+    }
+    fn part1(&self, data: &Self::Input) -> error::Result<Answer> {
+        Ok(count_safe_reports(data, 0).into())
+    }
+    fn part2(&self, data: &Self::Input) -> error::Result<Answer> {
+        Ok(count_safe_reports(data, 1).into())
+    }
+}
+
+fn parse<R: BufRead>(reader: R) -> error::Result<Vec<Vec<u32>>> {
+    reader
         .lines()
-        .map_while(Result::ok)
-        .map(|line| {
+        .enumerate()
+        .map(|(index, line)| {
+            let line = line?;
             line.split_whitespace()
-                .map(|num| num.parse::<u32>())
-                .collect::<Result<Vec<_>, _>>()
+                .map(|num| error::number(index + 1, &line, num))
+                .collect()
         })
-        .collect::<Result<Vec<_>, _>>()
-        .expect("Could not parse file");
-    let safe_report_count = data
-        .iter()
-        .filter(|report| is_safe_report(report, 1))
-        .count();
-    print!("Safe reports: {}", safe_report_count);
-    Ok(())
+        .collect()
+}
+
+fn count_safe_reports(data: &[Vec<u32>], error_tolerance: u8) -> usize {
+    data.iter()
+        .filter(|report| is_safe_report(report, error_tolerance))
+        .count()
 }
 
 pub fn is_safe_report(report: &[u32], error_tolerance: u8) -> bool {
@@ -64,8 +81,12 @@ pub fn is_safe_report(report: &[u32], error_tolerance: u8) -> bool {
         true
     } else if error_tolerance > 0 {
         for i in 0..report.len() {
+            if cancel::cancelled() {
+                return false;
+            }
             let mut skip_vec = report.to_owned();
             skip_vec.remove(i);
+            log::trace!("retrying {report:?} without level {}", i + 1);
             if is_safe_report(&skip_vec, error_tolerance - 1) {
                 return true;
             }
@@ -82,27 +103,27 @@ mod no_tolerance {
 
     #[test]
     fn test_safe_increasing() {
-        assert!(is_safe_report(&vec![7, 6, 4, 2, 1], 0));
+        assert!(is_safe_report(&[7, 6, 4, 2, 1], 0));
     }
     #[test]
     fn test_safe_decreasing() {
-        assert!(is_safe_report(&vec![1, 3, 6, 7, 9], 0));
+        assert!(is_safe_report(&[1, 3, 6, 7, 9], 0));
     }
     #[test]
     fn test_unsafe_big_increase() {
-        assert!(!is_safe_report(&vec![1, 2, 7, 8, 9], 0));
+        assert!(!is_safe_report(&[1, 2, 7, 8, 9], 0));
     }
     #[test]
     fn test_unsafe_big_decrease() {
-        assert!(!is_safe_report(&vec![9, 7, 6, 2, 1], 0));
+        assert!(!is_safe_report(&[9, 7, 6, 2, 1], 0));
     }
     #[test]
     fn test_unsafe_no_change() {
-        assert!(!is_safe_report(&vec![8, 6, 4, 4, 1], 0));
+        assert!(!is_safe_report(&[8, 6, 4, 4, 1], 0));
     }
     #[test]
     fn test_unsafe_unexpected_decrease() {
-        assert!(!is_safe_report(&vec![1, 3, 2, 4, 5], 0));
+        assert!(!is_safe_report(&[1, 3, 2, 4, 5], 0));
     }
 }
 
@@ -112,30 +133,30 @@ mod one_tolerance {
 
     #[test]
     fn test_safe_increasing() {
-        assert!(is_safe_report(&vec![7, 6, 4, 2, 1], 1));
+        assert!(is_safe_report(&[7, 6, 4, 2, 1], 1));
     }
     #[test]
     fn test_safe_decreasing() {
-        assert!(is_safe_report(&vec![1, 3, 6, 7, 9], 1));
+        assert!(is_safe_report(&[1, 3, 6, 7, 9], 1));
     }
     #[test]
     fn test_unsafe_big_increase() {
-        assert!(!is_safe_report(&vec![1, 2, 7, 8, 9], 1));
+        assert!(!is_safe_report(&[1, 2, 7, 8, 9], 1));
     }
     #[test]
     fn test_unsafe_big_decrease() {
-        assert!(!is_safe_report(&vec![9, 7, 6, 2, 1], 1));
+        assert!(!is_safe_report(&[9, 7, 6, 2, 1], 1));
     }
     #[test]
     fn test_safe_no_change() {
-        assert!(is_safe_report(&vec![8, 6, 4, 4, 1], 1));
+        assert!(is_safe_report(&[8, 6, 4, 4, 1], 1));
     }
     #[test]
     fn test_safe_unexpected_decrease() {
-        assert!(is_safe_report(&vec![1, 3, 2, 4, 5], 1));
+        assert!(is_safe_report(&[1, 3, 2, 4, 5], 1));
     }
     #[test]
     fn test_safe_remove_first() {
-        assert!(is_safe_report(&vec![2, 8, 9, 10, 11], 1));
+        assert!(is_safe_report(&[2, 8, 9, 10, 11], 1));
     }
 }
@@ -1,22 +1,35 @@
+use crate::error;
+use crate::solution::{Answer, Solution};
 use regex::Regex;
-use std::fs::File;
-use std::io::{self, BufReader, Read};
+use std::io::BufRead;
 
-pub fn day3() -> io::Result<()> {
-    let mut data: BufReader<File> = BufReader::new(File::open("./src/day3/input.txt")?);
-    let mut input_str = String::new();
-    data.read_to_string(&mut input_str)?;
-    println!(
-        "part 1: {}",
-        MultParser::new(input_str.clone())
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = String;
+    const DAY: u8 = 3;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+        read_input(reader)
+    }
+    fn part1(&self, input_str: &Self::Input) -> error::Result<Answer> {
+        Ok(MultParser::new(input_str.clone())
             .parse_simple()
             .calculate_total()
-    );
-    println!(
-        "part 2: {}",
-        MultParser::new(input_str).parse().calculate_total()
-    );
-    Ok(())
+            .into())
+    }
+    fn part2(&self, input_str: &Self::Input) -> error::Result<Answer> {
+        Ok(MultParser::new(input_str.clone())
+            .parse()
+            .calculate_total()
+            .into())
+    }
+}
+
+fn read_input<R: BufRead>(mut reader: R) -> error::Result<String> {
+    let mut input_str = String::new();
+    reader.read_to_string(&mut input_str)?;
+    Ok(input_str)
 }
 
 struct MultParser {
@@ -1,25 +1,47 @@
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
-
-pub fn day4() -> io::Result<()> {
-    let grid = parser("input.txt")?;
-    println!("Part1: {}", WordSearcher::from(grid.clone()).search("XMAS"));
-    println!("Part2: {}", WordSearcher::from(grid).search_mas());
-    Ok(())
+use crate::error::{self, Error};
+use crate::input::ParserInput;
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Vec<char>>;
+    const DAY: u8 = 4;
+    const GRID: bool = true;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+        parser(reader)
+    }
+    fn part1(&self, grid: &Self::Input) -> error::Result<Answer> {
+        Ok(WordSearcher::from(grid.clone()).search("XMAS").into())
+    }
+    fn part2(&self, grid: &Self::Input) -> error::Result<Answer> {
+        Ok(WordSearcher::from(grid.clone()).search_mas().into())
+    }
 }
 
-fn parser(file_name: &str) -> io::Result<Vec<Vec<char>>> {
-    let root = "./src/day4";
-    let file = File::open(format!("{root}/{file_name}"))?;
-    let reader = BufReader::new(file);
-    Ok(reader
-        .lines()
-        .map_while(Result::ok)
-        .map(|line| line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>())
+// The searches index neighbouring rows, so every row has to be as wide as the first
+pub fn parser<I: ParserInput>(input: I) -> error::Result<Vec<Vec<char>>> {
+    let reader = input.open(Day4::DAY)?;
+    let mut grid: Vec<Vec<char>> = vec![];
+    for (index, line) in reader.lines().enumerate() {
+        let row = line?.chars().collect::<Vec<_>>();
+        if let Some(width) = grid.first().map(Vec::len) {
+            if row.len() != width {
+                return Err(Error::parse(
+                    index + 1,
+                    row.len().min(width) + 1,
+                    format!("expected {width} letters, got {}", row.len()),
+                ));
+            }
+        }
+        grid.push(row);
+    }
+    Ok(grid)
 }
 
-struct WordSearcher {
+pub struct WordSearcher {
     grid: Vec<Vec<char>>,
 }
 
@@ -146,6 +168,14 @@ mod word_searcher {
 
     use super::WordSearcher;
 
+    #[test]
+    fn test_ragged_grid() {
+        assert_eq!(
+            parser("XMAS\nXMA".as_bytes()).unwrap_err().to_string(),
+            "2:4: expected 4 letters, got 3"
+        );
+    }
+
     #[test]
     fn test_simple() {
         assert_eq!(
@@ -188,3 +218,17 @@ mod word_searcher {
         );
     }
 }
+
+#[cfg(test)]
+mod test_solution {
+    use super::Day4;
+    use crate::input::example;
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn test_parts() {
+        let input = Day4.parse(&mut example(4)).unwrap();
+        assert_eq!(Day4.part1(&input).unwrap(), Answer::Number(18));
+        assert_eq!(Day4.part2(&input).unwrap(), Answer::Number(9));
+    }
+}
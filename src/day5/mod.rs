@@ -1,58 +1,72 @@
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
-
-pub fn day5() -> io::Result<()> {
-    println!(
-        "Part1: {}",
-        parser("./src/day5/input")?.get_valid_middle_total()
-    );
-    println!(
-        "Part2: Not working {}",
-        parser("./src/day5/input")?.get_corrected_middle_total()
-    );
-    Ok(())
+use crate::error::{self, Error};
+use crate::input::ParserInput;
+use crate::inspect::{histogram, Stat};
+use crate::log;
+use crate::solution::{Answer, Solution};
+use std::cmp::Ordering;
+use std::io::BufRead;
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Updater;
+    const DAY: u8 = 5;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+        parser(reader)
+    }
+    fn part1(&self, updater: &Self::Input) -> error::Result<Answer> {
+        Ok(updater.get_valid_middle_total().into())
+    }
+    fn part2(&self, updater: &Self::Input) -> error::Result<Answer> {
+        Ok(updater.get_corrected_middle_total().into())
+    }
+    fn inspect(&self, updater: &Self::Input) -> Vec<Stat> {
+        vec![
+            Stat::new("rules", updater.rules.len()),
+            Stat::new("updates", updater.updates.len()),
+            Stat::new(
+                "update lengths",
+                histogram(updater.updates.iter().map(Vec::len)),
+            ),
+        ]
+    }
 }
 
-fn parser(file_path: &str) -> io::Result<Updater> {
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
+pub fn parser<I: ParserInput>(input: I) -> error::Result<Updater> {
+    let reader = input.open(Day5::DAY)?;
     let mut rules: Vec<(u16, u16)> = vec![];
     let mut updates: Vec<Vec<u16>> = vec![];
     let mut parsing_rules = true;
-    for line in reader.lines().map_while(Result::ok) {
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
         if parsing_rules {
             if line.is_empty() {
                 parsing_rules = false;
                 continue;
             }
-            let mut split = line.split('|');
+            let (before, after) = line
+                .split_once('|')
+                .ok_or_else(|| Error::parse(line_number, 1, "expected a rule like 47|53"))?;
             rules.push((
-                split
-                    .next()
-                    .expect("rule part 1 was not found")
-                    .parse()
-                    .expect("rule part 1 was not a number"),
-                split
-                    .next()
-                    .expect("rule part 2 was not found")
-                    .parse()
-                    .expect("rule part 2 was not a number"),
+                error::number(line_number, &line, before)?,
+                error::number(line_number, &line, after)?,
             ));
         } else {
             updates.push(
                 line.split(',')
-                    .map(|elem| elem.parse().expect("Update included a non number"))
-                    .collect(),
+                    .map(|elem| error::number(line_number, &line, elem))
+                    .collect::<error::Result<_>>()?,
             );
         }
     }
-    // println!("Rules: {:?}", rules);
-    // println!("Updates: {:?}", updates);
+    log::debug!("{} rules, {} updates", rules.len(), updates.len());
     Ok(Updater { rules, updates })
 }
 
 #[derive(PartialEq, Eq, Debug)]
-struct Updater {
+pub struct Updater {
     rules: Vec<(u16, u16)>,
     updates: Vec<Vec<u16>>,
 }
@@ -76,45 +90,28 @@ impl Updater {
             }
         })
     }
-    fn sort_update(&self, _update: &[u16]) -> Vec<u16> {
-        // let applicable_rules: Vec<(u16, u16)> = self
-        //     .rules
-        //     .clone()
-        //     .into_iter()
-        //     .filter(|rule| update.contains(&rule.0) && update.contains(&rule.1))
-        //     .collect();
-        // println!("{:?}", update);
-        // println!("{:?}", applicable_rules);
-        // let mut sorted_update = update.clone();
-        // for (idx, num) in sorted_update.iter().enumerate() {
-        //     for rule in applicable_rules.iter().filter(|rule| rule.1 == num) {
-
-        //     }
-        // }
-        // Start with the rule who's first value isn't in any other rules' second value
-        // let mut current_rule = applicable_rules.iter().find(|rule1| {
-        //     applicable_rules
-        //         .iter()
-        //         .any(|rule2| rule2.1 == rule1.0)
-        // });
-        // let mut sorted_vec = vec![];
-        // while let Some(current_rule_unwrapped) = current_rule {
-        //     sorted_vec.push(current_rule_unwrapped.0);
-        //     current_rule = applicable_rules
-        //         .iter()
-        //         .find(|rule| rule.0 == current_rule_unwrapped.1);
-        //     if current_rule.is_none() {
-        //         sorted_vec.push(current_rule_unwrapped.1);
-        //     }
-        // }
-        // sorted_update
-        unimplemented!();
+    // Puts the pages in the order the rules give them: a page comes before another when a rule
+    // says so, and pages no rule relates keep their order
+    fn sort_update(&self, update: &[u16]) -> Vec<u16> {
+        let mut sorted_update = update.to_vec();
+        sorted_update.sort_by(|a, b| {
+            if self.rules.contains(&(*a, *b)) {
+                Ordering::Less
+            } else if self.rules.contains(&(*b, *a)) {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        });
+        log::trace!("sorted {update:?} into {sorted_update:?}");
+        sorted_update
     }
     fn get_middle_val(update: &[u16]) -> u16 {
         *update.split_at(update.len() / 2).1.first().unwrap()
     }
     fn is_update_good(&self, update: &[u16]) -> bool {
         self.rules.iter().all(|rule| {
+            log::trace!("checking rule {}|{} against {update:?}", rule.0, rule.1);
             let mut found_second = false;
             for num in update.iter() {
                 if num == &rule.1 {
@@ -135,6 +132,14 @@ mod parser {
 
     use super::parser;
 
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parser("47|53\n97-13".as_bytes()).unwrap_err().to_string(),
+            "2:1: expected a rule like 47|53"
+        );
+    }
+
     #[test]
     fn test_parser() {
         assert_eq!(
@@ -200,44 +205,58 @@ mod is_update_good {
     #[test]
     fn test_is_update_good() {
         let updater = parser("./src/day5/test_data").unwrap();
-        assert!(updater.is_update_good(&vec![75, 47, 61, 53, 29]),);
-        assert!(updater.is_update_good(&vec![97, 61, 53, 29, 13]),);
-        assert!(updater.is_update_good(&vec![75, 29, 13]),);
-        assert!(!updater.is_update_good(&vec![75, 97, 47, 61, 53]),);
-        assert!(!updater.is_update_good(&vec![61, 13, 29]),);
-        assert!(!updater.is_update_good(&vec![97, 13, 75, 29, 47]),);
+        assert!(updater.is_update_good(&[75, 47, 61, 53, 29]),);
+        assert!(updater.is_update_good(&[97, 61, 53, 29, 13]),);
+        assert!(updater.is_update_good(&[75, 29, 13]),);
+        assert!(!updater.is_update_good(&[75, 97, 47, 61, 53]),);
+        assert!(!updater.is_update_good(&[61, 13, 29]),);
+        assert!(!updater.is_update_good(&[97, 13, 75, 29, 47]),);
     }
 }
 #[cfg(test)]
-// mod sort_update {
-
-//     use super::parser;
-
-//     #[test]
-//     fn test_sort_update() {
-//         let updater = parser("./src/day5/test_data").unwrap();
-//         assert_eq!(
-//             updater.sort_update(&vec![75, 97, 47, 61, 53]),
-//             vec![97, 75, 47, 61, 53]
-//         );
-//         assert_eq!(updater.sort_update(&vec![61, 13, 29]), vec![61, 29, 13]);
-//         assert_eq!(
-//             updater.sort_update(&vec![97, 13, 75, 29, 47]),
-//             vec![97, 75, 47, 29, 13]
-//         );
-//     }
-// }
+mod sort_update {
+
+    use super::parser;
+
+    #[test]
+    fn test_sort_update() {
+        let updater = parser("./src/day5/test_data").unwrap();
+        assert_eq!(
+            updater.sort_update(&[75, 97, 47, 61, 53]),
+            vec![97, 75, 47, 61, 53]
+        );
+        assert_eq!(updater.sort_update(&[61, 13, 29]), vec![61, 29, 13]);
+        assert_eq!(
+            updater.sort_update(&[97, 13, 75, 29, 47]),
+            vec![97, 75, 47, 29, 13]
+        );
+    }
+}
 #[cfg(test)]
 mod get_middle_val {
     use super::Updater;
 
     #[test]
     fn test_get_middle_val() {
-        assert_eq!(Updater::get_middle_val(&vec![75, 47, 61, 53, 29]), 61);
-        assert_eq!(Updater::get_middle_val(&vec![97, 61, 53, 29, 13]), 53);
-        assert_eq!(Updater::get_middle_val(&vec![75, 29, 13]), 29);
-        assert_eq!(Updater::get_middle_val(&vec![75, 97, 47, 61, 53]), 47);
-        assert_eq!(Updater::get_middle_val(&vec![61, 13, 29]), 13);
-        assert_eq!(Updater::get_middle_val(&vec![97, 13, 75, 29, 47]), 75);
+        assert_eq!(Updater::get_middle_val(&[75, 47, 61, 53, 29]), 61);
+        assert_eq!(Updater::get_middle_val(&[97, 61, 53, 29, 13]), 53);
+        assert_eq!(Updater::get_middle_val(&[75, 29, 13]), 29);
+        assert_eq!(Updater::get_middle_val(&[75, 97, 47, 61, 53]), 47);
+        assert_eq!(Updater::get_middle_val(&[61, 13, 29]), 13);
+        assert_eq!(Updater::get_middle_val(&[97, 13, 75, 29, 47]), 75);
+    }
+}
+
+#[cfg(test)]
+mod test_solution {
+    use super::Day5;
+    use crate::input::example;
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn test_parts() {
+        let input = Day5.parse(&mut example(5)).unwrap();
+        assert_eq!(Day5.part1(&input).unwrap(), Answer::Number(143));
+        assert_eq!(Day5.part2(&input).unwrap(), Answer::Number(123));
     }
 }
@@ -1,3 +1,6 @@
+use crate::cancel;
+use crate::log;
+use crate::phase;
 use std::collections::HashSet;
 use std::fmt::{self, Display};
 
@@ -45,6 +48,10 @@ enum CycleResult {
 
 impl GuardMap {
     fn cycle(&mut self) -> CycleResult {
+        // A map whose loop detection misses would otherwise walk forever
+        if cancel::cancelled() {
+            return CycleResult::Done;
+        }
         let next_cell_coordinates = match self.guard.direction {
             Direction::Up => {
                 if self.guard.position.1 == 0 {
@@ -98,15 +105,22 @@ impl GuardMap {
                 self.guard.position = next_cell_coordinates;
             }
         }
+        log::trace!(
+            "guard at {:?} facing {:?}",
+            self.guard.position,
+            self.guard.direction
+        );
         CycleResult::Continuable
     }
     pub fn run(&mut self) -> &mut Self {
-        let mut continuable = true;
-        while continuable {
-            if !matches!(self.cycle(), CycleResult::Continuable) {
-                continuable = false;
+        phase::time("walk", || {
+            let mut continuable = true;
+            while continuable {
+                if !matches!(self.cycle(), CycleResult::Continuable) {
+                    continuable = false;
+                }
             }
-        }
+        });
         self
     }
     pub fn count_visited(&self) -> u16 {
@@ -121,37 +135,43 @@ impl GuardMap {
     pub fn count_loop_spots(&self) -> u16 {
         let mut copy_map = self.clone();
         copy_map.run();
-        let mut loop_count = 0;
-        for (y, line) in copy_map.map.iter().enumerate() {
-            for (x, position) in line.iter().enumerate() {
-                if let Position::Visited(_) = position {
-                    //Skip if we're looking at the original guard position
-                    if (x, y) != self.guard.position {
-                        let mut guard_map_with_new_obstacle = self.clone();
-                        *guard_map_with_new_obstacle
-                            .map
-                            .get_mut(y)
-                            .unwrap()
-                            .get_mut(x)
-                            .unwrap() = Position::Obstacle;
-                        let mut continuable = true;
-                        while continuable {
-                            match guard_map_with_new_obstacle.cycle() {
-                                CycleResult::Continuable => {}
-                                CycleResult::Loop => {
-                                    loop_count += 1;
-                                    continuable = false;
-                                }
-                                CycleResult::Done => {
-                                    continuable = false;
+        // One phase for all the trials, as timing each of them would cost more than the trial
+        phase::time("obstacles", || {
+            let mut loop_count = 0;
+            for (y, line) in copy_map.map.iter().enumerate() {
+                for (x, position) in line.iter().enumerate() {
+                    if cancel::cancelled() {
+                        return loop_count;
+                    }
+                    if let Position::Visited(_) = position {
+                        //Skip if we're looking at the original guard position
+                        if (x, y) != self.guard.position {
+                            let mut guard_map_with_new_obstacle = self.clone();
+                            *guard_map_with_new_obstacle
+                                .map
+                                .get_mut(y)
+                                .unwrap()
+                                .get_mut(x)
+                                .unwrap() = Position::Obstacle;
+                            let mut continuable = true;
+                            while continuable {
+                                match guard_map_with_new_obstacle.cycle() {
+                                    CycleResult::Continuable => {}
+                                    CycleResult::Loop => {
+                                        loop_count += 1;
+                                        continuable = false;
+                                    }
+                                    CycleResult::Done => {
+                                        continuable = false;
+                                    }
                                 }
                             }
                         }
                     }
                 }
             }
-        }
-        loop_count
+            loop_count
+        })
     }
 }
 
@@ -213,6 +233,13 @@ impl Display for GuardMap {
 #[cfg(test)]
 mod visited_spaces {
     use crate::day6::parser::parser;
+    use crate::snapshot::assert_snapshot;
+
+    #[test]
+    fn test_display_after_run() {
+        let mut guard_map = parser("test_data").unwrap();
+        assert_snapshot("day6/example_walked", guard_map.run());
+    }
 
     #[test]
     fn test_count_visited() {
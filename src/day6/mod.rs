@@ -1,11 +1,66 @@
-use crate::day6::parser::*;
-use std::io::{self};
+use crate::error;
+use crate::inspect::Stat;
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
 
 mod guard_map;
 mod parser;
 
-pub fn day6() -> io::Result<()> {
-    println!("Part1: {}", parser("input")?.run().count_visited());
-    println!("Part2: {}", parser("input")?.count_loop_spots());
-    Ok(())
+pub use guard_map::{Direction, Guard, GuardMap, Position};
+pub use parser::parser;
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = GuardMap;
+    const DAY: u8 = 6;
+    const GRID: bool = true;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+        parser(reader)
+    }
+    fn part1(&self, guard_map: &Self::Input) -> error::Result<Answer> {
+        Ok(guard_map.clone().run().count_visited().into())
+    }
+    fn part2(&self, guard_map: &Self::Input) -> error::Result<Answer> {
+        Ok(guard_map.count_loop_spots().into())
+    }
+    fn show(&self, guard_map: &Self::Input) -> Option<String> {
+        Some(guard_map.to_string())
+    }
+    fn inspect(&self, guard_map: &Self::Input) -> Vec<Stat> {
+        let obstacles = guard_map
+            .map
+            .iter()
+            .flatten()
+            .filter(|position| matches!(position, Position::Obstacle))
+            .count();
+        let (x, y) = guard_map.guard.position;
+        vec![
+            Stat::new("obstacles", obstacles),
+            Stat::new(
+                "guard",
+                format!(
+                    "line {}, column {}, facing {:?}",
+                    y + 1,
+                    x + 1,
+                    guard_map.guard.direction
+                ),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test_solution {
+    use super::Day6;
+    use crate::input::example;
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn test_parts() {
+        let input = Day6.parse(&mut example(6)).unwrap();
+        assert_eq!(Day6.part1(&input).unwrap(), Answer::Number(41));
+        assert_eq!(Day6.part2(&input).unwrap(), Answer::Number(6));
+    }
 }
@@ -1,41 +1,67 @@
 use crate::day6::guard_map::*;
 
+use super::Day6;
+use crate::error::{self, Error};
+use crate::input::ParserInput;
+use crate::solution::Solution;
 use std::collections::HashSet;
 use std::fmt;
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
+use std::io::BufRead;
 
-pub fn parser(file_name: &str) -> io::Result<GuardMap> {
-    let root = "./src/day6";
-    let file = File::open(format!("{root}/{file_name}"))?;
-    let reader = BufReader::new(file);
-    let mut guard = None;
-    let map = reader
-        .lines()
-        .map_while(Result::ok)
-        .enumerate()
-        .map(|(y, line)| {
-            // y starts from the top and goes downward
-            line.char_indices()
-                .map(|(x, value)| {
-                    let parsed = parse_char(value)?;
-                    if let Some(direction) = parsed.1 {
-                        guard = Some(Guard {
-                            position: (x, y),
-                            direction,
-                        });
+// The walk indexes the map by position, so every row has to be as wide as the first, and there is
+// exactly one guard to walk
+pub fn parser<I: ParserInput>(input: I) -> error::Result<GuardMap> {
+    let reader = input.open(Day6::DAY)?;
+    let mut guard: Option<Guard> = None;
+    let mut map: Vec<Vec<Position>> = vec![];
+    for (y, line) in reader.lines().enumerate() {
+        // y starts from the top and goes downward
+        let row = line?
+            .char_indices()
+            .map(|(x, value)| {
+                let parsed = parse_char(value)
+                    .map_err(|error| Error::parse(y + 1, x + 1, error.to_string()))?;
+                if let Some(direction) = parsed.1 {
+                    if let Some(first) = &guard {
+                        let (first_x, first_y) = first.position;
+                        return Err(Error::parse(
+                            y + 1,
+                            x + 1,
+                            format!(
+                                "found a second guard, the first is at {}:{}",
+                                first_y + 1,
+                                first_x + 1
+                            ),
+                        ));
                     }
-                    Ok::<Position, ParsePositionError>(parsed.0)
-                })
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .collect::<Result<Vec<_>, _>>()
-        .expect("Could not parse grid");
+                    guard = Some(Guard {
+                        position: (x, y),
+                        direction,
+                    });
+                }
+                Ok(parsed.0)
+            })
+            .collect::<error::Result<Vec<_>>>()?;
+        if let Some(width) = map.first().map(Vec::len) {
+            if row.len() != width {
+                return Err(Error::parse(
+                    y + 1,
+                    row.len().min(width) + 1,
+                    format!("expected {width} positions, got {}", row.len()),
+                ));
+            }
+        }
+        map.push(row);
+    }
 
-    Ok(GuardMap {
-        map,
-        guard: guard.expect("Could not find guard"),
-    })
+    let guard = guard.ok_or_else(|| {
+        Error::parse(
+            map.len().max(1),
+            1,
+            "reached the end of the map without finding the guard",
+        )
+    })?;
+    Ok(GuardMap { map, guard })
 }
 
 // Returns a position, and if there's a guard there, his direction. Throws a ParsePositionError for invalid characters
@@ -93,13 +119,37 @@ impl fmt::Debug for ParsePositionError {
 mod sort_update {
 
     use super::parser;
+    use crate::snapshot::assert_snapshot;
 
     #[test]
-    fn test_parser() {
-        let guard_map = parser("test_data").unwrap();
+    fn test_parse_errors() {
+        assert_eq!(
+            parser("..#\n.x.".as_bytes()).err().unwrap().to_string(),
+            "2:2: Could not parse x to a Position"
+        );
+        assert_eq!(
+            parser("..#\n.^\n".as_bytes()).err().unwrap().to_string(),
+            "2:3: expected 3 positions, got 2"
+        );
+        assert_eq!(
+            parser("..#\n.^.\n..>".as_bytes())
+                .err()
+                .unwrap()
+                .to_string(),
+            "3:3: found a second guard, the first is at 2:2"
+        );
+    }
+
+    #[test]
+    fn test_missing_guard() {
         assert_eq!(
-            format!("{guard_map}"),
-            format!("....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...")
+            parser("..#\n...".as_bytes()).err().unwrap().to_string(),
+            "2:1: reached the end of the map without finding the guard"
         );
     }
+
+    #[test]
+    fn test_parser() {
+        assert_snapshot("day6/example", parser("test_data").unwrap());
+    }
 }
@@ -1,99 +1,121 @@
+use crate::cancel;
+use crate::log;
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct Equation {
     pub result: u64,
     pub operands: Vec<u64>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Operator {
     Add,
     Multiply,
     Concatenate,
 }
 
-impl Equation {
-    fn find_valid_operators_for_2(val1: u64, val2: u64, result: u64) -> Option<Operator> {
-        // println!("{val1} || {val2} = {}", format!("{val1}{val2}").parse::<u64>().unwrap());
-        if val1 + val2 == result {
-            Some(Operator::Add)
-        } else if val1 * val2 == result {
-            Some(Operator::Multiply)
-        } else if format!("{val1}{val2}").parse::<u64>().unwrap() == result {
-            // println!("{}", format!("{val1}{val2}").parse::<u64>().unwrap());
-            Some(Operator::Concatenate)
-        } else {
-            None
+impl Operator {
+    /// The operators of part 1; part 2 adds concatenation
+    pub const ARITHMETIC: [Operator; 2] = [Operator::Add, Operator::Multiply];
+    pub const ALL: [Operator; 3] = [Operator::Add, Operator::Multiply, Operator::Concatenate];
+
+    // The operator applied to both values, or None when that overflows a u64, which can't equal
+    // any result
+    fn apply(self, val1: u64, val2: u64) -> Option<u64> {
+        match self {
+            Operator::Add => val1.checked_add(val2),
+            Operator::Multiply => val1.checked_mul(val2),
+            Operator::Concatenate => {
+                let digits = val2.checked_ilog10().map_or(1, |log| log + 1);
+                10u64
+                    .checked_pow(digits)
+                    .and_then(|shift| val1.checked_mul(shift))
+                    .and_then(|shifted| shifted.checked_add(val2))
+            }
         }
     }
 
+    fn symbol(self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Multiply => "*",
+            Operator::Concatenate => "||",
+        }
+    }
+}
+
+impl Equation {
+    fn find_valid_operators_for_2(
+        val1: u64,
+        val2: u64,
+        result: u64,
+        allowed: &[Operator],
+    ) -> Option<Operator> {
+        log::trace!("trying {allowed:?} between {val1} and {val2} for {result}");
+        allowed
+            .iter()
+            .copied()
+            .find(|operator| operator.apply(val1, val2) == Some(result))
+    }
+
     pub fn find_valid_operators(&self) -> Option<Vec<Operator>> {
-        Self::find_valid_operators_inner(self.operands.clone(), self.result).map(|mut operators| {
-            // Since the operators were figured out depth first, we need to reverse the list
-            operators.reverse();
-            operators
-        })
+        self.find_valid_operators_among(&Operator::ALL)
     }
 
-    fn find_valid_operators_inner(operands: Vec<u64>, result: u64) -> Option<Vec<Operator>> {
-        // Base case where only 2 operands are left
-        if operands.len() == 2 {
-            return Self::find_valid_operators_for_2(
-                *operands.first().unwrap(),
-                *operands.get(1).unwrap(),
-                result,
-            )
-            .map(|operator| vec![operator]);
-        }
+    /// Like `find_valid_operators`, but only tries the `allowed` operators, in that order
+    pub fn find_valid_operators_among(&self, allowed: &[Operator]) -> Option<Vec<Operator>> {
+        Self::find_valid_operators_inner(&self.operands, self.result, allowed).map(
+            |mut operators| {
+                // Since the operators were figured out depth first, we need to reverse the list
+                operators.reverse();
+                operators
+            },
+        )
+    }
 
-        // Check of the next operator is Add
-        let mut operands_clone_for_add = operands.clone();
-        let (first_operand_for_add, rest_of_the_operands_for_add) =
-            operands_clone_for_add.split_first_mut().unwrap();
-        let next_operand_for_add = rest_of_the_operands_for_add.get_mut(0).unwrap();
-        *next_operand_for_add += *first_operand_for_add;
-        if let Some(mut add_result) =
-            Self::find_valid_operators_inner(rest_of_the_operands_for_add.to_vec(), result)
-        {
-            add_result.push(Operator::Add);
-            return Some(add_result);
+    fn find_valid_operators_inner(
+        operands: &[u64],
+        result: u64,
+        allowed: &[Operator],
+    ) -> Option<Vec<Operator>> {
+        if cancel::cancelled() {
+            return None;
         }
-
-        // Check if the next operator is Multiply
-        let mut operands_clone_for_mult = operands.clone();
-        let (first_operand_for_mult, rest_of_the_operands_for_mult) =
-            operands_clone_for_mult.split_first_mut().unwrap();
-        let next_operand_for_mult = rest_of_the_operands_for_mult.get_mut(0).unwrap();
-        *next_operand_for_mult *= *first_operand_for_mult;
-        if let Some(mut mult_result) =
-            Self::find_valid_operators_inner(rest_of_the_operands_for_mult.to_vec(), result)
-        {
-            mult_result.push(Operator::Multiply);
-            return Some(mult_result);
+        let [first, second, rest @ ..] = operands else {
+            return None;
+        };
+        // Base case where only 2 operands are left
+        if rest.is_empty() {
+            return Self::find_valid_operators_for_2(*first, *second, result, allowed)
+                .map(|operator| vec![operator]);
         }
 
-        // Check if the next operator is Concatenate
-        let mut operands_clone_for_concat = operands.clone();
-        let (first_operand_for_concat, rest_of_the_operands_for_concat) =
-            operands_clone_for_concat.split_first_mut().unwrap();
-        let next_operand_for_concat = rest_of_the_operands_for_concat.get_mut(0).unwrap();
-        *next_operand_for_concat =
-            format!("{}{}", *first_operand_for_concat, *next_operand_for_concat)
-                .parse::<u64>()
-                .unwrap();
-        // println!("{:?}", rest_of_the_operands_for_concat);
-        if let Some(mut concat_result) =
-            Self::find_valid_operators_inner(rest_of_the_operands_for_concat.to_vec(), result)
-        {
-            concat_result.push(Operator::Concatenate);
-            return Some(concat_result);
+        // Folds the first two operands with each operator in turn and searches the rest
+        for operator in allowed {
+            let Some(combined) = operator.apply(*first, *second) else {
+                continue;
+            };
+            let remaining = [&[combined], rest].concat();
+            log::trace!("trying {} with {remaining:?}", operator.symbol());
+            if let Some(mut operators) =
+                Self::find_valid_operators_inner(&remaining, result, allowed)
+            {
+                operators.push(*operator);
+                return Some(operators);
+            }
         }
 
         None
     }
 
-    pub fn get_total_calibration_results(equations: Vec<Self>) -> u64 {
+    pub fn get_total_calibration_results(equations: &[Self]) -> u64 {
+        Self::get_total_calibration_results_among(equations, &Operator::ALL)
+    }
+
+    /// The sum of the results of the equations that the `allowed` operators can make true
+    pub fn get_total_calibration_results_among(equations: &[Self], allowed: &[Operator]) -> u64 {
         equations.iter().fold(0, |acc, equation| {
-            if equation.find_valid_operators().is_some() {
+            if equation.find_valid_operators_among(allowed).is_some() {
                 acc + equation.result
             } else {
                 acc
@@ -112,7 +134,7 @@ mod test_equation {
     fn test_find_valid_operators() {
         let equations = parser("test_data").unwrap();
         assert_eq!(
-            equations.get(0).unwrap().find_valid_operators(),
+            equations.first().unwrap().find_valid_operators(),
             Some(vec![Operator::Multiply])
         );
         assert_eq!(
@@ -148,6 +170,21 @@ mod test_equation {
     fn test_get_total_calibration_results() {
         let equations = parser("test_data").unwrap();
         // assert_eq!(Equation::get_total_calibration_results(equations), 3749);
-        assert_eq!(Equation::get_total_calibration_results(equations), 11387);
+        assert_eq!(Equation::get_total_calibration_results(&equations), 11387);
+        assert_eq!(
+            Equation::get_total_calibration_results_among(&equations, &Operator::ARITHMETIC),
+            3749
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let equation = Equation {
+            result: 1,
+            operands: vec![u64::MAX / 2, 3, 9_999_999_999],
+        };
+        assert_eq!(equation.find_valid_operators(), None);
+        assert_eq!(Operator::Concatenate.apply(12, 0), Some(120));
+        assert_eq!(Operator::Concatenate.apply(u64::MAX / 10, 10), None);
     }
 }
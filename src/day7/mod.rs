@@ -1,13 +1,55 @@
-use crate::day7::{equation::Equation, parser::parser};
-use std::io;
+use crate::error;
+use crate::inspect::{histogram, Stat};
+use crate::solution::{Answer, Solution};
+use std::io::BufRead;
 
 mod equation;
 mod parser;
 
-pub fn day7() -> io::Result<()> {
-    println!(
-        "Part2: {}",
-        Equation::get_total_calibration_results(parser("input")?)
-    );
-    Ok(())
+pub use equation::{Equation, Operator};
+pub use parser::parser;
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<Equation>;
+    const DAY: u8 = 7;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+        parser(reader)
+    }
+    fn part1(&self, equations: &Self::Input) -> error::Result<Answer> {
+        Ok(Equation::get_total_calibration_results_among(equations, &Operator::ARITHMETIC).into())
+    }
+    fn part2(&self, equations: &Self::Input) -> error::Result<Answer> {
+        Ok(Equation::get_total_calibration_results(equations).into())
+    }
+    fn inspect(&self, equations: &Self::Input) -> Vec<Stat> {
+        let max_result = equations.iter().map(|equation| equation.result).max();
+        vec![
+            Stat::new("equations", equations.len()),
+            Stat::new(
+                "operands per equation",
+                histogram(equations.iter().map(|equation| equation.operands.len())),
+            ),
+            Stat::new(
+                "max result",
+                max_result.map_or("-".to_owned(), |max| max.to_string()),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test_solution {
+    use super::Day7;
+    use crate::input::example;
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn test_parts() {
+        let input = Day7.parse(&mut example(7)).unwrap();
+        assert_eq!(Day7.part1(&input).unwrap(), Answer::Number(3749));
+        assert_eq!(Day7.part2(&input).unwrap(), Answer::Number(11387));
+    }
 }
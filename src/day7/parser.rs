@@ -1,28 +1,38 @@
+use super::Day7;
 use crate::day7::equation::Equation;
+use crate::error::{self, Error};
+use crate::input::ParserInput;
+use crate::solution::Solution;
 
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
+use std::io::BufRead;
 
-pub fn parser(file_name: &str) -> io::Result<Vec<Equation>> {
-    let root = "./src/day7";
-    let file = File::open(format!("{root}/{file_name}"))?;
-    let reader = BufReader::new(file);
-    Ok(reader
+// The operator search needs at least two operands to combine
+pub fn parser<I: ParserInput>(input: I) -> error::Result<Vec<Equation>> {
+    let reader = input.open(Day7::DAY)?;
+    reader
         .lines()
-        .map_while(Result::ok)
-        .map(|line| {
-            let mut split = line.split(':');
-            let result = split.next().unwrap().parse().unwrap();
-            let operands = split
-                .next()
-                .unwrap()
-                .trim()
+        .enumerate()
+        .map(|(index, line)| {
+            let line = line?;
+            let line_number = index + 1;
+            let (result, operands) = line.split_once(':').ok_or_else(|| {
+                Error::parse(line_number, 1, "expected an equation like 190: 10 19")
+            })?;
+            let result = error::number(line_number, &line, result)?;
+            let operands = operands
                 .split_ascii_whitespace()
-                .map(|operand_string| operand_string.parse().unwrap())
-                .collect();
-            Equation { result, operands }
+                .map(|operand_string| error::number(line_number, &line, operand_string))
+                .collect::<error::Result<Vec<_>>>()?;
+            if operands.len() < 2 {
+                return Err(Error::parse(
+                    line_number,
+                    line.len() + 1,
+                    "expected at least two operands",
+                ));
+            }
+            Ok(Equation { result, operands })
         })
-        .collect::<Vec<_>>())
+        .collect()
 }
 
 #[cfg(test)]
@@ -31,11 +41,47 @@ mod test_parser {
     use super::parser;
     use crate::day7::equation::Equation;
 
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parser("190: 10 19\n3267 81".as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "2:1: expected an equation like 190: 10 19"
+        );
+    }
+
+    #[test]
+    fn test_too_few_operands() {
+        assert!(parser("190: 10 x".as_bytes()).is_err());
+        assert_eq!(
+            parser("190: 190".as_bytes()).unwrap_err().to_string(),
+            "1:9: expected at least two operands"
+        );
+    }
+
+    #[test]
+    fn test_parse_str() {
+        assert_eq!(
+            parser("190: 10 19\n3267: 81 40 27".as_bytes()).unwrap(),
+            vec![
+                Equation {
+                    result: 190,
+                    operands: vec![10, 19]
+                },
+                Equation {
+                    result: 3267,
+                    operands: vec![81, 40, 27]
+                }
+            ]
+        );
+    }
+
     #[test]
     fn test_parser() {
         let equations = parser("test_data").unwrap();
         assert_eq!(
-            *equations.get(0).unwrap(),
+            *equations.first().unwrap(),
             Equation {
                 result: 190,
                 operands: vec![10, 19]
@@ -3,7 +3,7 @@ use std::{
     fmt,
 };
 
-#[derive(Eq, PartialEq, Hash, Debug)]
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
 pub struct Coordinate {
     pub x: usize,
     pub y: usize,
@@ -53,7 +53,7 @@ impl Coordinate {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct AntennaMap {
     pub antennae: HashMap<char, HashSet<Coordinate>>,
     pub antinodes: HashSet<Coordinate>,
@@ -173,21 +173,17 @@ mod test_antenna_map {
     // just testing to make sure this works right
     use super::{AntennaMap, Coordinate};
     use crate::day8::parser::parser;
+    use crate::snapshot::assert_snapshot;
 
     #[test]
     fn test_display() {
-        //expected
-        let expected_antinodeless = "............\n........0...\n.....0......\n.......0....\n....0.......\n......A.....\n............\n............\n........A...\n.........A..\n............\n............".to_owned();
-        let expected_with_antinodes = "......#....#\n...#....0...\n....#0....#.\n..#....0....\n....0....#..\n.#....#.....\n...#........\n#......#....\n........A...\n.........A..\n..........#.\n..........#.".to_owned();
-
         //given
         let mut antenna_map = parser("test_input").unwrap();
 
         //then
-        assert_eq!(format!("{antenna_map}"), expected_antinodeless);
+        assert_snapshot("day8/example", &antenna_map);
         antenna_map.calc_antinodes();
-        println!("{antenna_map}");
-        assert_eq!(format!("{antenna_map}"), expected_with_antinodes);
+        assert_snapshot("day8/example_antinodes", &antenna_map);
     }
     #[test]
     fn test_calc_antinodes() {
@@ -1,17 +1,74 @@
-use crate::day8::parser::parser;
-use std::io;
+use crate::error;
+use crate::inspect::Stat;
+use crate::solution::{Answer, Solution};
+use std::collections::HashSet;
+use std::io::BufRead;
 
 mod antenna_map;
 mod parser;
 
-pub fn day8() -> io::Result<()> {
-    println!(
-        "Part1: {}",
-        (parser("input")?.calc_antinodes().count_antinodes())
-    );
-    println!(
-        "Part2: {}",
-        (parser("input")?.calc_harmonic_antinodes().count_antinodes())
-    );
-    Ok(())
+pub use antenna_map::{AntennaMap, Coordinate};
+pub use parser::parser;
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = AntennaMap;
+    const DAY: u8 = 8;
+    const GRID: bool = true;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+        parser(reader)
+    }
+    fn part1(&self, antenna_map: &Self::Input) -> error::Result<Answer> {
+        Ok(antenna_map
+            .clone()
+            .calc_antinodes()
+            .count_antinodes()
+            .into())
+    }
+    fn part2(&self, antenna_map: &Self::Input) -> error::Result<Answer> {
+        Ok(antenna_map
+            .clone()
+            .calc_harmonic_antinodes()
+            .count_antinodes()
+            .into())
+    }
+    fn show(&self, antenna_map: &Self::Input) -> Option<String> {
+        Some(antenna_map.to_string())
+    }
+    fn inspect(&self, antenna_map: &Self::Input) -> Vec<Stat> {
+        let mut frequencies = antenna_map
+            .antennae
+            .iter()
+            .map(|(frequency, antennae)| format!("{frequency}: {}", antennae.len()))
+            .collect::<Vec<_>>();
+        frequencies.sort();
+        vec![
+            Stat::new("frequencies", antenna_map.antennae.len()),
+            Stat::new(
+                "antennas",
+                antenna_map
+                    .antennae
+                    .values()
+                    .map(HashSet::len)
+                    .sum::<usize>(),
+            ),
+            Stat::new("antennas per frequency", frequencies.join(", ")),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test_solution {
+    use super::Day8;
+    use crate::input::example;
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn test_parts() {
+        let input = Day8.parse(&mut example(8)).unwrap();
+        assert_eq!(Day8.part1(&input).unwrap(), Answer::Number(14));
+        assert_eq!(Day8.part2(&input).unwrap(), Answer::Number(34));
+    }
 }
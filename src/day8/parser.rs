@@ -1,20 +1,25 @@
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
+use std::io::BufRead;
 
+use super::Day8;
 use crate::day8::antenna_map::AntennaMap;
+use crate::error::{self, Error};
+use crate::input::ParserInput;
+use crate::solution::Solution;
 
 use super::antenna_map::Coordinate;
 
-pub fn parser(file_name: &str) -> io::Result<AntennaMap> {
-    let root = "./src/day8";
-    let file = File::open(format!("{root}/{file_name}"))?;
-    let reader = BufReader::new(file);
+pub fn parser<I: ParserInput>(input: I) -> error::Result<AntennaMap> {
+    let reader = input.open(Day8::DAY)?;
     let mut antennae: HashMap<char, HashSet<Coordinate>> = HashMap::new();
     // let mut antenna_map = AntennaMap::new();
     let mut max_y = 0;
     let mut max_x = 0;
-    for (y, line) in reader.lines().map_while(Result::ok).enumerate() {
+    for (y, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            return Err(Error::parse(y + 1, 1, "empty line in the map"));
+        }
         for (x, value) in line
             .char_indices()
             .filter(|(_, val)| val.is_ascii_alphanumeric())
@@ -43,6 +48,14 @@ mod test_parser {
     use super::parser;
     use crate::day8::antenna_map::{AntennaMap, Coordinate};
 
+    #[test]
+    fn test_empty_line() {
+        assert_eq!(
+            parser("a..\n\n..a".as_bytes()).unwrap_err().to_string(),
+            "2:1: empty line in the map"
+        );
+    }
+
     #[test]
     fn test_parser() {
         let antenna_map = parser("test_input").unwrap();
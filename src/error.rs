@@ -0,0 +1,111 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything that can go wrong while parsing an input or solving a part
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// Malformed input. Lines and columns count from 1; the file is filled in by whoever opened it.
+    Parse {
+        file: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input parsed, but has no answer
+    NoSolution(String),
+}
+
+impl Error {
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Error::Parse {
+            file: None,
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    // Names the file a parse error came from, for the diagnostic
+    pub fn in_file(self, path: &Path) -> Self {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                message,
+                ..
+            } => Error::Parse {
+                file: Some(path.to_owned()),
+                line,
+                column,
+                message,
+            },
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{error}"),
+            Error::Parse {
+                file,
+                line,
+                column,
+                message,
+            } => {
+                if let Some(file) = file {
+                    write!(f, "{}:", file.display())?;
+                }
+                write!(f, "{line}:{column}: {message}")
+            }
+            Error::NoSolution(message) => write!(f, "no solution: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+// The column at which `field` starts, where `field` is a slice of `line`
+pub fn column(line: &str, field: &str) -> usize {
+    field.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Parses `field`, a slice of the given line, reporting where it is if it isn't a number
+pub fn number<T: FromStr>(line_number: usize, line: &str, field: &str) -> Result<T> {
+    field.parse().map_err(|_| {
+        Error::parse(
+            line_number,
+            column(line, field),
+            format!("expected a number, got {field:?}"),
+        )
+    })
+}
+
+#[cfg(test)]
+mod test_error {
+    use super::*;
+
+    #[test]
+    fn test_number() {
+        let line = "190: 10 x9";
+        assert_eq!(number::<u64>(3, line, &line[5..7]).unwrap(), 10);
+        let error = number::<u64>(3, line, &line[8..]).unwrap_err();
+        assert_eq!(error.to_string(), "3:9: expected a number, got \"x9\"");
+        assert_eq!(
+            error.in_file(Path::new("day7/input.txt")).to_string(),
+            "day7/input.txt:3:9: expected a number, got \"x9\""
+        );
+    }
+}
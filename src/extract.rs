@@ -0,0 +1,311 @@
+use crate::cli::{Failure, Part};
+use crate::verify::ExpectedAnswers;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An example input from the puzzle text, with the name of the fixture it goes in
+#[derive(Debug, PartialEq, Eq)]
+pub struct Example {
+    pub file: String,
+    pub contents: String,
+}
+
+/// The answer the puzzle text gives for one part of one of the examples
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExampleAnswer {
+    pub file: String,
+    pub part: Part,
+    pub answer: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Extracted {
+    pub examples: Vec<Example>,
+    pub answers: Vec<ExampleAnswer>,
+}
+
+/// Pulls the examples and their answers out of a saved puzzle page. Each part is an `<article>`.
+/// Part 1's example is its first `<pre><code>` block; the later blocks only illustrate it. A
+/// block in part 2 is a new example when the paragraph before it mentions an example, otherwise
+/// part 2 reuses part 1's. The answer for a part is the last emphasized `<code>` in its article.
+pub fn extract(page: &str) -> Extracted {
+    let article = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+    let block = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    let answer =
+        Regex::new(r"(?s)<code><em>(.*?)</em></code>|<em><code>(.*?)</code></em>").unwrap();
+
+    let mut extracted = Extracted::default();
+    for (article, part) in article
+        .captures_iter(page)
+        .map(|captures| captures.get(1).unwrap().as_str())
+        .zip([Part::One, Part::Two])
+    {
+        let example = block.captures_iter(article).find_map(|captures| {
+            let whole = captures.get(0).unwrap();
+            let introduced = article[..whole.start()]
+                .rsplit("<p>")
+                .next()
+                .unwrap_or_default()
+                .to_lowercase()
+                .contains("example");
+            (part == Part::One || introduced).then(|| text(captures.get(1).unwrap().as_str()))
+        });
+        if let Some(contents) = example {
+            let file = match extracted.examples.len() {
+                0 => "example.txt".to_owned(),
+                count => format!("example{}.txt", count + 1),
+            };
+            extracted.examples.push(Example { file, contents });
+        }
+        let last_answer = answer
+            .captures_iter(article)
+            .last()
+            .map(|captures| text(captures.get(1).or(captures.get(2)).unwrap().as_str()));
+        if let (Some(answer), Some(example)) = (last_answer, extracted.examples.last()) {
+            extracted.answers.push(ExampleAnswer {
+                file: example.file.clone(),
+                part,
+                answer: answer.trim().to_owned(),
+            });
+        }
+    }
+    extracted
+}
+
+// The text of some HTML: tags dropped and the entities the puzzle pages use decoded
+fn text(html: &str) -> String {
+    let tag = Regex::new(r"<[^>]*>").unwrap();
+    tag.replace_all(html, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Writes the examples into `dir` and adds their answers to the answers file. A fixture that is
+/// empty, as `aoc new` leaves it, is filled in; one with other contents is left alone and reported,
+/// and so is an answer that disagrees with one already recorded.
+pub fn write(
+    extracted: &Extracted,
+    day: u8,
+    dir: &Path,
+    answers_path: &Path,
+) -> Result<Vec<PathBuf>, Failure> {
+    let io_error = |path: &Path, error: std::io::Error| {
+        Failure::Extract(format!("{}: {error}", path.display()))
+    };
+    let answers = match fs::read_to_string(answers_path) {
+        Ok(answers) => answers,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(io_error(answers_path, error)),
+    };
+    let expected = ExpectedAnswers::parse(&answers)
+        .map_err(|error| Failure::Extract(format!("{}: {error}", answers_path.display())))?;
+
+    // Everything is checked before anything is written
+    let mut new_lines = vec![];
+    for answer in &extracted.answers {
+        match expected.get(day, &answer.file, answer.part) {
+            Some(recorded) if recorded.matches(&answer.answer) => {}
+            Some(recorded) => {
+                return Err(Failure::Extract(format!(
+                    "{} already expects {recorded} for {} part {}, not {}",
+                    answers_path.display(),
+                    answer.file,
+                    answer.part.number(),
+                    answer.answer
+                )))
+            }
+            None => new_lines.push(format!(
+                "{day} {} {} {}",
+                answer.file,
+                answer.part.number(),
+                answer.answer
+            )),
+        }
+    }
+    let mut fixtures = vec![];
+    for example in &extracted.examples {
+        let path = dir.join(&example.file);
+        match fs::read_to_string(&path) {
+            // Editors and `git` may have dropped or added a newline at the end
+            Ok(existing) if existing.trim_end() == example.contents.trim_end() => {}
+            Ok(existing) if !existing.trim().is_empty() => {
+                return Err(Failure::Extract(format!(
+                    "{} already exists with other contents",
+                    path.display()
+                )))
+            }
+            _ => fixtures.push((path, &example.contents)),
+        }
+    }
+
+    fs::create_dir_all(dir).map_err(|error| io_error(dir, error))?;
+    let mut written = vec![];
+    for (path, contents) in fixtures {
+        fs::write(&path, contents).map_err(|error| io_error(&path, error))?;
+        written.push(path);
+    }
+    if !new_lines.is_empty() {
+        fs::write(answers_path, insert_answers(&answers, day, &new_lines))
+            .map_err(|error| io_error(answers_path, error))?;
+        written.push(answers_path.to_owned());
+    }
+    Ok(written)
+}
+
+/// Puts the new lines after the day's existing answers, or in a paragraph of their own at the end
+pub fn insert_answers(answers: &str, day: u8, new_lines: &[String]) -> String {
+    let mut lines = answers.lines().map(str::to_owned).collect::<Vec<_>>();
+    let prefix = format!("{day} ");
+    match lines.iter().rposition(|line| line.starts_with(&prefix)) {
+        Some(last) => {
+            lines.splice(last + 1..last + 1, new_lines.iter().cloned());
+        }
+        None => {
+            if lines.last().is_some_and(|line| !line.is_empty()) {
+                lines.push(String::new());
+            }
+            lines.extend(new_lines.iter().cloned());
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod test_extract {
+    use super::*;
+    use std::env;
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 6: Guard Gallivant ---</h2>
+<p>For example, suppose you have the following map:</p>
+<pre><code>....#.....
+.&lt;..#...
+</code></pre>
+<p>After a few steps:</p>
+<pre><code>....#.....
+.X..#...
+</code></pre>
+<p>In this example, the guard will visit <code><em>41</em></code> distinct positions.</p>
+</article>
+<p>Your puzzle answer was <code>4602</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Option one, put a printing press next to the guard:</p>
+<pre><code>....#.....
+.O..#...
+</code></pre>
+<p>In this example, there are <em><code>6</code></em> different positions.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_extract() {
+        assert_eq!(
+            extract(PAGE),
+            Extracted {
+                examples: vec![Example {
+                    file: "example.txt".to_owned(),
+                    contents: "....#.....\n.<..#...\n".to_owned()
+                }],
+                answers: vec![
+                    ExampleAnswer {
+                        file: "example.txt".to_owned(),
+                        part: Part::One,
+                        answer: "41".to_owned()
+                    },
+                    ExampleAnswer {
+                        file: "example.txt".to_owned(),
+                        part: Part::Two,
+                        answer: "6".to_owned()
+                    }
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn test_second_example() {
+        let page = "<article><p>For example:</p><pre><code>xmul(2,4)\n</code></pre>\
+                    <p>This adds up to <code><em>161</em></code>.</p></article>\
+                    <article><p>For example, a new program:</p><pre><code>don't()\n</code></pre>\
+                    <p>This time the sum is <code><em>48</em></code>.</p></article>";
+        let extracted = extract(page);
+        assert_eq!(extracted.examples.len(), 2);
+        assert_eq!(extracted.examples[1].file, "example2.txt");
+        assert_eq!(extracted.answers[1].file, "example2.txt");
+        assert_eq!(extracted.answers[1].answer, "48");
+    }
+
+    #[test]
+    fn test_write() {
+        let dir = env::temp_dir().join(format!("aoc-extract-{}", std::process::id()));
+        let answers = dir.join("answers.txt");
+        fs::create_dir_all(dir.join("day6")).unwrap();
+        fs::write(dir.join("day6/example.txt"), "").unwrap();
+        fs::write(
+            &answers,
+            "# answers\n\n5 example.txt 1 143\n\n7 example.txt 2 11387\n",
+        )
+        .unwrap();
+
+        let written = write(&extract(PAGE), 6, &dir.join("day6"), &answers).unwrap();
+        assert_eq!(written.len(), 2);
+        assert_eq!(
+            fs::read_to_string(&answers).unwrap(),
+            "# answers\n\n5 example.txt 1 143\n\n7 example.txt 2 11387\n\n\
+             6 example.txt 1 41\n6 example.txt 2 6\n"
+        );
+        // Running it again changes nothing
+        assert_eq!(
+            write(&extract(PAGE), 6, &dir.join("day6"), &answers)
+                .unwrap()
+                .len(),
+            0
+        );
+
+        fs::write(dir.join("day6/example.txt"), "#..\n").unwrap();
+        assert!(matches!(
+            write(&extract(PAGE), 6, &dir.join("day6"), &answers),
+            Err(Failure::Extract(_))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fixture_without_newline() {
+        let dir = env::temp_dir().join(format!("aoc-extract-newline-{}", std::process::id()));
+        let answers = dir.join("answers.txt");
+        fs::create_dir_all(dir.join("day6")).unwrap();
+        let extracted = extract(PAGE);
+        let contents = extracted.examples[0].contents.trim_end();
+        fs::write(dir.join("day6/example.txt"), contents).unwrap();
+        fs::write(&answers, "6 example.txt 1 41\n6 example.txt 2 6\n").unwrap();
+
+        assert_eq!(
+            write(&extracted, 6, &dir.join("day6"), &answers)
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("day6/example.txt")).unwrap(),
+            contents
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_insert_answers() {
+        assert_eq!(
+            insert_answers(
+                "6 example.txt 1 41\n\n7 input.txt 1 3\n",
+                6,
+                &["6 example.txt 2 6".to_owned()]
+            ),
+            "6 example.txt 1 41\n6 example.txt 2 6\n\n7 input.txt 1 3\n"
+        );
+    }
+}
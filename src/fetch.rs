@@ -0,0 +1,109 @@
+use crate::cli::Failure;
+use crate::client::Client;
+use crate::log;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already there, so nothing was requested
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Downloads the input for the given day to `path`, unless it is already there. Inputs never change,
+/// so a cached one is never downloaded again.
+pub fn fetch(client: &Client, year: u16, day: u8, path: &Path) -> Result<Fetched, Failure> {
+    if path.exists() {
+        return Ok(Fetched::Cached(path.to_owned()));
+    }
+    log::debug!("downloading day {day} of {year}");
+    let response = client.get(&format!("/{year}/day/{day}/input"))?;
+    match response.status {
+        200 => {}
+        404 => {
+            return Err(Failure::Network(format!(
+                "day {day} of {year} isn't unlocked yet"
+            )))
+        }
+        400 | 500 if response.body.contains("log in") => {
+            return Err(Failure::Network(
+                "the session token was rejected; it may have expired".to_owned(),
+            ))
+        }
+        status => {
+            return Err(Failure::Network(format!(
+                "HTTP {status}: {}",
+                response.body.trim()
+            )))
+        }
+    }
+    let error = |error: std::io::Error| Failure::Network(format!("{}: {error}", path.display()));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(error)?;
+    }
+    // Written under another name first, so an interrupted write doesn't leave a truncated input
+    // that would then count as cached
+    let partial = path.with_extension("part");
+    fs::write(&partial, &response.body).map_err(error)?;
+    fs::rename(&partial, path).map_err(error)?;
+    Ok(Fetched::Downloaded(path.to_owned()))
+}
+
+#[cfg(test)]
+mod test_fetch {
+    use super::*;
+    use crate::http::stub;
+    use std::env;
+    use std::time::Duration;
+
+    fn client(url: &str) -> Client {
+        Client::new(url, "abc".to_owned()).throttle(Duration::ZERO, None)
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let path = dir.join("day3/input.txt");
+        let (url, server) = stub::serve(vec![stub::response(200, "mul(2,4)\n")]);
+
+        assert_eq!(
+            fetch(&client(&url), 2024, 3, &path).unwrap(),
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "mul(2,4)\n");
+        // The stub only answers once, so this would fail if it made a request
+        assert_eq!(
+            fetch(&client(&url), 2024, 3, &path).unwrap(),
+            Fetched::Cached(path.clone())
+        );
+
+        let request = &server.join().unwrap()[0];
+        assert!(request.starts_with("GET /2024/day/3/input HTTP/1.0\r\n"));
+        assert!(request.contains("\r\nCookie: session=abc\r\n"));
+        assert!(request.contains("\r\nUser-Agent: AdventOfCode2024-aoc/"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = env::temp_dir().join(format!("aoc-fetch-errors-{}", std::process::id()));
+        let path = dir.join("day25/input.txt");
+        let (url, server) = stub::serve(vec![
+            stub::response(404, "Not found"),
+            stub::response(
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+            ),
+        ]);
+        let failure = fetch(&client(&url), 2024, 25, &path).unwrap_err();
+        assert_eq!(
+            failure.to_string(),
+            "Request failed: day 25 of 2024 isn't unlocked yet"
+        );
+        let failure = fetch(&client(&url), 2024, 25, &path).unwrap_err();
+        assert!(failure.to_string().contains("session token was rejected"));
+        assert!(!path.exists());
+        server.join().unwrap();
+    }
+}
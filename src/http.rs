@@ -0,0 +1,222 @@
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Sends a request and reads the whole response. Plain `http://` URLs are spoken to directly over
+/// HTTP/1.0, which is all a local stand-in server needs. `https://` goes through `curl`, since
+/// the standard library has no TLS.
+pub fn send(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> io::Result<Response> {
+    if let Some(rest) = url.strip_prefix("http://") {
+        send_plain(method, rest, headers, body)
+    } else if url.starts_with("https://") {
+        send_curl(method, url, headers, body)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported URL: {url}"),
+        ))
+    }
+}
+
+fn send_plain(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> io::Result<Response> {
+    let (host, path) = match url.find('/') {
+        Some(slash) => url.split_at(slash),
+        None => (url, "/"),
+    };
+    let address = if host.contains(':') {
+        host.to_owned()
+    } else {
+        format!("{host}:80")
+    };
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    // HTTP/1.0 keeps the response unchunked and the connection closes after it
+    let mut request = format!("{method} {path} HTTP/1.0\r\nHost: {host}\r\n");
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    if let Some(body) = body {
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("\r\n");
+    request.push_str(body.unwrap_or_default());
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    parse_response(&response)
+}
+
+fn parse_response(response: &str) -> io::Result<Response> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response");
+    let (head, body) = response.split_once("\r\n\r\n").ok_or_else(invalid)?;
+    let status = head
+        .lines()
+        .next()
+        .and_then(|status_line| status_line.split_whitespace().nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid)?;
+    Ok(Response {
+        status,
+        body: body.to_owned(),
+    })
+}
+
+// The headers go to curl on stdin so that the session cookie never shows up in the process list.
+// curl appends the status code after the body.
+fn send_curl(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> io::Result<Response> {
+    let mut command = Command::new("curl");
+    command
+        .args([
+            "--silent",
+            "--show-error",
+            "--request",
+            method,
+            "--header",
+            "@-",
+        ])
+        .args(["--max-time", &TIMEOUT.as_secs().to_string()])
+        .args(["--write-out", "\n%{http_code}"]);
+    if let Some(body) = body {
+        command.args(["--data-raw", body]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let header_lines = headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}\n"))
+        .collect::<String>();
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(header_lines.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+    let output = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = output
+        .rsplit_once('\n')
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no status from curl"))?;
+    Ok(Response {
+        status: status
+            .trim()
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "no status from curl"))?,
+        body: body.to_owned(),
+    })
+}
+
+#[cfg(test)]
+pub mod stub {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// A local server that answers each of the given responses to one request, in order, and hands
+    /// back the requests it received
+    pub fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = vec![];
+                let mut buffer = [0; 4096];
+                // Reads until the end of the headers plus whatever body was declared
+                loop {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.strip_prefix("Content-Length: "))
+                            .map_or(0, |length| length.parse().unwrap());
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                    if read == 0 {
+                        break;
+                    }
+                }
+                requests.push(String::from_utf8(request).unwrap());
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    pub fn response(status: u16, body: &str) -> String {
+        format!("HTTP/1.1 {status} Whatever\r\nContent-Type: text/plain\r\n\r\n{body}")
+    }
+}
+
+#[cfg(test)]
+mod test_http {
+    use super::*;
+
+    #[test]
+    fn test_send_plain() {
+        let (url, server) = stub::serve(vec![stub::response(200, "1 2\n3 4\n")]);
+        let response = send(
+            "POST",
+            &format!("{url}/2024/day/1/answer"),
+            &[("Cookie", "session=abc")],
+            Some("level=1&answer=42"),
+        )
+        .unwrap();
+        assert_eq!(
+            response,
+            Response {
+                status: 200,
+                body: "1 2\n3 4\n".to_owned()
+            }
+        );
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2024/day/1/answer HTTP/1.0\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=1&answer=42"));
+    }
+
+    #[test]
+    fn test_unsupported_url() {
+        assert!(send("GET", "ftp://example.com", &[], None).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::phase;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, StdinLock};
+use std::path::{Path, PathBuf};
+
+/// Environment variable that overrides where inputs are read from, below `--input-dir`
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+pub const INPUT_FILE: &str = "input.txt";
+/// The input path that stands for stdin
+pub const STDIN: &str = "-";
+pub const ANSWERS_FILE: &str = "answers.txt";
+pub const SUBMISSIONS_FILE: &str = "submissions.txt";
+#[cfg(test)]
+pub const EXAMPLE_FILE: &str = "example.txt";
+
+/// Finds the input files for each day, laid out as `<dir>/dayN/<file name>`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InputResolver {
+    dir: PathBuf,
+}
+
+impl InputResolver {
+    // An explicit directory wins, then the environment variable, then the source tree
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let dir = dir
+            .or_else(|| env::var_os(INPUT_DIR_ENV).map(PathBuf::from))
+            .unwrap_or_else(default_dir);
+        InputResolver { dir }
+    }
+
+    /// Always resolves inside the source tree, regardless of the environment. The checked-in
+    /// examples live there, so tests use this.
+    #[cfg(test)]
+    pub fn source_tree() -> Self {
+        InputResolver { dir: default_dir() }
+    }
+
+    pub fn path(&self, day: u8, file_name: &str) -> PathBuf {
+        self.dir.join(format!("day{day}")).join(file_name)
+    }
+
+    // The expected answers for every day sit next to the day directories
+    pub fn answers_path(&self) -> PathBuf {
+        self.dir.join(ANSWERS_FILE)
+    }
+
+    // Every answer sent to the site, next to the expected answers
+    pub fn submissions_path(&self) -> PathBuf {
+        self.dir.join(SUBMISSIONS_FILE)
+    }
+}
+
+// Anchored on the manifest so that neither the binary nor `cargo test` depend on the working
+// directory
+fn default_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+/// Reads a whole input into memory, from stdin for `-`, timed as the `io` phase
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    phase::time(phase::IO, || {
+        let mut contents = vec![];
+        open(path)?.read_to_end(&mut contents)?;
+        Ok(contents)
+    })
+}
+
+// Opens an input for parsing, reading stdin for `-`
+pub fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    if path == Path::new(STDIN) {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+/// What the day parsers read from: any reader, or the name of a file in the day's directory under
+/// `InputResolver::new(None)`, such as `"input.txt"`. A name with a directory in it is opened as
+/// given.
+pub trait ParserInput {
+    type Reader: BufRead;
+
+    fn open(self, day: u8) -> io::Result<Self::Reader>;
+}
+
+impl ParserInput for &str {
+    type Reader = BufReader<File>;
+
+    fn open(self, day: u8) -> io::Result<Self::Reader> {
+        let path = if Path::new(self).components().count() > 1 {
+            PathBuf::from(self)
+        } else {
+            InputResolver::new(None).path(day, self)
+        };
+        let file = File::open(&path).map_err(|error| {
+            io::Error::new(error.kind(), format!("{}: {error}", path.display()))
+        })?;
+        Ok(BufReader::new(file))
+    }
+}
+
+impl<'a, R: BufRead + ?Sized> ParserInput for &'a mut R {
+    type Reader = &'a mut R;
+
+    fn open(self, _day: u8) -> io::Result<Self::Reader> {
+        Ok(self)
+    }
+}
+
+impl<'a> ParserInput for &'a [u8] {
+    type Reader = &'a [u8];
+
+    fn open(self, _day: u8) -> io::Result<Self::Reader> {
+        Ok(self)
+    }
+}
+
+impl<R: Read> ParserInput for BufReader<R> {
+    type Reader = Self;
+
+    fn open(self, _day: u8) -> io::Result<Self::Reader> {
+        Ok(self)
+    }
+}
+
+impl<'a> ParserInput for StdinLock<'a> {
+    type Reader = Self;
+
+    fn open(self, _day: u8) -> io::Result<Self::Reader> {
+        Ok(self)
+    }
+}
+
+// The example from the puzzle text for the given day
+#[cfg(test)]
+pub fn example(day: u8) -> BufReader<File> {
+    let path = InputResolver::source_tree().path(day, EXAMPLE_FILE);
+    BufReader::new(File::open(&path).unwrap_or_else(|error| panic!("{}: {error}", path.display())))
+}
+
+#[cfg(test)]
+mod input_resolver {
+    use super::*;
+
+    #[test]
+    fn test_explicit_dir() {
+        let resolver = InputResolver::new(Some(PathBuf::from("/tmp/inputs")));
+        assert_eq!(
+            resolver.path(6, INPUT_FILE),
+            PathBuf::from("/tmp/inputs/day6/input.txt")
+        );
+    }
+
+    #[test]
+    fn test_source_tree() {
+        assert!(InputResolver::source_tree()
+            .path(1, INPUT_FILE)
+            .ends_with("src/day1/input.txt"));
+        assert!(InputResolver::source_tree().path(6, EXAMPLE_FILE).is_file());
+    }
+}
+
+#[cfg(test)]
+mod parser_input {
+    use super::*;
+
+    #[test]
+    fn test_file_names() {
+        let mut contents = String::new();
+        "test_data"
+            .open(6)
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert!(contents.starts_with("....#....."));
+
+        let error = "missing.txt".open(6).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().contains("day6/missing.txt"));
+    }
+
+    #[test]
+    fn test_paths() {
+        let path = InputResolver::source_tree().path(6, EXAMPLE_FILE);
+        assert_eq!(path.to_str().unwrap().open(1).unwrap().lines().count(), 10);
+    }
+}
@@ -0,0 +1,274 @@
+use crate::cli::Failure;
+use crate::error::Error;
+use crate::input;
+use crate::runner::catch;
+use crate::solution::DynSolution;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::path::Path;
+
+/// A named figure about an input, such as its width or how many rules it has
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Stat {
+    pub name: String,
+    pub value: String,
+}
+
+impl Stat {
+    pub fn new(name: &str, value: impl Display) -> Self {
+        Stat {
+            name: name.to_owned(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// Something about an input that is probably a mistake, with the line it is on when it has one
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Anomaly {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Anomaly {
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        Anomaly {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Inspection {
+    pub stats: Vec<Stat>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .stats
+            .iter()
+            .map(|stat| stat.name.len())
+            .max()
+            .unwrap_or(0);
+        for stat in &self.stats {
+            writeln!(f, "{:<width$}  {}", stat.name, stat.value)?;
+        }
+        if self.anomalies.is_empty() {
+            write!(f, "No anomalies")
+        } else {
+            write!(f, "Anomalies:")?;
+            for anomaly in &self.anomalies {
+                write!(f, "\n  {anomaly}")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Reads and parses an input and gathers figures about its shape: its lines, its grid for days
+/// that have one, and whatever the day reports about what it parsed. Non-ASCII characters, blank
+/// lines at the end, ragged grid lines and the first parse error are flagged as anomalies, in
+/// line order.
+pub fn inspect(solution: &dyn DynSolution, path: &Path) -> Result<Inspection, Failure> {
+    let contents =
+        input::read(path).map_err(|error| Failure::MissingInput(path.to_owned(), error))?;
+    let text = String::from_utf8_lossy(&contents);
+    let lines = text.lines().collect::<Vec<_>>();
+
+    let mut inspection = Inspection::default();
+    inspection.stats.push(Stat::new("lines", lines.len()));
+    inspection.stats.push(Stat::new("bytes", contents.len()));
+    inspection.anomalies.extend(line_anomalies(&lines));
+    if solution.grid() {
+        let (stats, anomalies) = grid(&lines);
+        inspection.stats.extend(stats);
+        inspection.anomalies.extend(anomalies);
+    }
+    match catch(|| solution.parse(&mut contents.as_slice())) {
+        Ok(Ok(parsed)) => inspection.stats.extend(solution.inspect(parsed.as_ref())),
+        Ok(Err(Error::Parse {
+            line,
+            column,
+            message,
+            ..
+        })) => inspection.anomalies.push(Anomaly::at(
+            line,
+            format!("doesn't parse at column {column}: {message}"),
+        )),
+        Ok(Err(error)) => inspection.anomalies.push(Anomaly {
+            line: None,
+            message: format!("doesn't parse: {error}"),
+        }),
+        Err(message) => inspection.anomalies.push(Anomaly {
+            line: None,
+            message: format!("the parser panicked: {message}"),
+        }),
+    }
+    inspection.anomalies.sort_by_key(|anomaly| anomaly.line);
+    Ok(inspection)
+}
+
+// Non-ASCII characters, which include bytes that aren't UTF-8, and blank lines at the end
+fn line_anomalies(lines: &[&str]) -> Vec<Anomaly> {
+    let mut anomalies = vec![];
+    if lines.is_empty() {
+        anomalies.push(Anomaly {
+            line: None,
+            message: "the input is empty".to_owned(),
+        });
+    }
+    for (index, line) in lines.iter().enumerate() {
+        if let Some((column, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii()) {
+            anomalies.push(Anomaly::at(
+                index + 1,
+                format!("non-ASCII character {c:?} at column {}", column + 1),
+            ));
+        }
+    }
+    let blank = lines
+        .iter()
+        .rev()
+        .take_while(|line| line.trim().is_empty())
+        .count();
+    if blank > 0 && blank < lines.len() {
+        anomalies.push(Anomaly::at(
+            lines.len() - blank + 1,
+            match blank {
+                1 => "blank line at the end".to_owned(),
+                _ => format!("{blank} blank lines at the end"),
+            },
+        ));
+    }
+    anomalies
+}
+
+// The width is the one most lines have, so that a single short line is the one reported. Blank
+// lines at the end are left out, as they are reported already.
+fn grid(lines: &[&str]) -> (Vec<Stat>, Vec<Anomaly>) {
+    let rows = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(&lines[..0], |last| &lines[..=last]);
+    let widths = rows
+        .iter()
+        .map(|row| row.chars().count())
+        .collect::<Vec<_>>();
+    let mut counts = BTreeMap::new();
+    for width in &widths {
+        *counts.entry(*width).or_insert(0) += 1;
+    }
+    let width = counts
+        .iter()
+        .max_by_key(|(width, count)| (**count, **width))
+        .map_or(0, |(width, _)| *width);
+    let anomalies = widths
+        .iter()
+        .enumerate()
+        .filter(|(_, row_width)| **row_width != width)
+        .map(|(index, row_width)| {
+            Anomaly::at(
+                index + 1,
+                format!("ragged line, {row_width} wide where the grid is {width}"),
+            )
+        })
+        .collect();
+    (
+        vec![Stat::new("width", width), Stat::new("height", rows.len())],
+        anomalies,
+    )
+}
+
+/// How often each value occurs, smallest value first, as `value: count` pairs
+pub fn histogram<T: Ord + Display>(values: impl IntoIterator<Item = T>) -> String {
+    let mut counts = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    counts
+        .iter()
+        .map(|(value, count)| format!("{value}: {count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test_inspect {
+    use super::*;
+    use crate::input::{InputResolver, EXAMPLE_FILE};
+    use crate::{day6, day7};
+    use std::{env, fs, process};
+
+    #[test]
+    fn test_line_anomalies() {
+        assert_eq!(
+            line_anomalies(&["..#", ".é.", "...", "", " "]),
+            [
+                Anomaly::at(2, "non-ASCII character 'é' at column 2"),
+                Anomaly::at(4, "2 blank lines at the end"),
+            ]
+        );
+        assert_eq!(line_anomalies(&["..#", "..."]), []);
+    }
+
+    #[test]
+    fn test_grid() {
+        let (stats, anomalies) = grid(&["....", "...", "....", "....", ""]);
+        assert_eq!(stats, [Stat::new("width", 4), Stat::new("height", 4)]);
+        assert_eq!(
+            anomalies,
+            [Anomaly::at(2, "ragged line, 3 wide where the grid is 4")]
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        assert_eq!(histogram([3, 5, 3, 2]), "2: 1, 3: 2, 5: 1");
+    }
+
+    #[test]
+    fn test_inspect_example() {
+        let path = InputResolver::source_tree().path(7, EXAMPLE_FILE);
+        let inspection = inspect(&day7::Day7, &path).unwrap();
+        assert!(inspection.anomalies.is_empty());
+        assert!(inspection
+            .stats
+            .contains(&Stat::new("operands per equation", "2: 3, 3: 3, 4: 3")));
+        assert!(inspection.stats.contains(&Stat::new("max result", 161011)));
+    }
+
+    #[test]
+    fn test_missing_guard() {
+        let path = env::temp_dir().join(format!("aoc-inspect-{}.txt", process::id()));
+        fs::write(&path, "..#\n...\n...\n").unwrap();
+        let inspection = inspect(&day6::Day6, &path).unwrap();
+        assert_eq!(
+            inspection.to_string(),
+            "lines   3\nbytes   12\nwidth   3\nheight  3\nAnomalies:\n  \
+             line 3: doesn't parse at column 1: reached the end of the map without finding the guard"
+        );
+
+        fs::write(&path, "..#\n.^.\n..\n\n").unwrap();
+        let inspection = inspect(&day6::Day6, &path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            inspection.anomalies,
+            [
+                Anomaly::at(3, "ragged line, 2 wide where the grid is 3"),
+                Anomaly::at(3, "doesn't parse at column 3: expected 3 positions, got 2"),
+                Anomaly::at(4, "blank line at the end"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Advent of Code 2024. Each `dayN` module exposes its `Solution` along with the parser and the
+//! types it builds, which are also re-exported here with `SOLUTIONS` and `run`. The `cli`,
+//! `config`, `log` and `commands` modules are what the `aoc` binary is made of; the rest of the
+//! runner stays inside the crate.
+
+pub(crate) mod alloc;
+pub(crate) mod bench;
+pub(crate) mod cancel;
+pub mod cli;
+pub(crate) mod client;
+pub mod commands;
+pub mod config;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod error;
+pub(crate) mod extract;
+pub(crate) mod fetch;
+pub(crate) mod http;
+pub(crate) mod input;
+pub(crate) mod inspect;
+pub mod log;
+pub(crate) mod output;
+pub(crate) mod phase;
+pub(crate) mod record;
+pub(crate) mod repl;
+pub mod runner;
+pub(crate) mod scaffold;
+pub(crate) mod sha256;
+#[cfg(test)]
+mod snapshot;
+pub mod solution;
+pub(crate) mod submit;
+pub(crate) mod verify;
+pub(crate) mod watch;
+
+pub use day4::WordSearcher;
+pub use day5::Updater;
+pub use day6::{Direction, Guard, GuardMap, Position};
+pub use day7::{Equation, Operator};
+pub use day8::{AntennaMap, Coordinate};
+pub use input::ParserInput;
+pub use runner::run;
+pub use solution::{Answer, Solution};
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+solution::days! {
+    day1 => Day1,
+    day2 => Day2,
+    day3 => Day3,
+    day4 => Day4,
+    day5 => Day5,
+    day6 => Day6,
+    day7 => Day7,
+    day8 => Day8,
+}
@@ -0,0 +1,125 @@
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+/// How much is written to stderr. Each level includes the ones before it.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub enum Level {
+    /// Nothing but the final error, with `-q`
+    Quiet,
+    #[default]
+    Warn,
+    /// With `-v`
+    Debug,
+    /// With `-vv`, including the trace points in the solvers' inner loops
+    Trace,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Quiet => "quiet",
+            Level::Warn => "warn",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+/// The logging flags, which can appear anywhere on the command line
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct LogOptions {
+    pub level: Level,
+    /// Only messages logged while one of these days runs are shown; empty means every day
+    pub days: Vec<u8>,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+// Bit N is set when day N is shown; all bits set means no filter
+static DAYS: AtomicU32 = AtomicU32::new(u32::MAX);
+
+thread_local! {
+    static DAY: Cell<Option<u8>> = const { Cell::new(None) };
+}
+
+pub fn init(options: &LogOptions) {
+    LEVEL.store(options.level as u8, Ordering::Relaxed);
+    let days = if options.days.is_empty() {
+        u32::MAX
+    } else {
+        options.days.iter().fold(0, |mask, day| {
+            mask | 1u32.checked_shl(u32::from(*day)).unwrap_or(0)
+        })
+    };
+    DAYS.store(days, Ordering::Relaxed);
+}
+
+/// Runs `f` with its messages attributed to `day`, for the day filter and the message prefix
+pub fn with_day<R>(day: u8, f: impl FnOnce() -> R) -> R {
+    let previous = DAY.replace(Some(day));
+    let result = f();
+    DAY.set(previous);
+    result
+}
+
+// Checked by the macros before the message is formatted, so a disabled trace point costs a load
+pub fn enabled(level: Level) -> bool {
+    if (level as u8) > LEVEL.load(Ordering::Relaxed) {
+        return false;
+    }
+    let days = DAYS.load(Ordering::Relaxed);
+    days == u32::MAX
+        || DAY
+            .get()
+            .is_none_or(|day| days & 1u32.checked_shl(u32::from(day)).unwrap_or(0) != 0)
+}
+
+pub fn write(level: Level, message: fmt::Arguments) {
+    match DAY.get() {
+        Some(day) => eprintln!("[{} day{day}] {message}", level.name()),
+        None => eprintln!("[{}] {message}", level.name()),
+    }
+}
+
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            $crate::log::write($level, format_args!($($arg)*));
+        }
+    };
+}
+
+macro_rules! warning {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Warn, $($arg)*) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Debug, $($arg)*) };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Trace, $($arg)*) };
+}
+
+pub(crate) use {debug, log, trace, warning};
+
+#[cfg(test)]
+mod test_log {
+    use super::*;
+
+    // The only test that changes the global settings, so it can't race another one
+    #[test]
+    fn test_enabled() {
+        init(&LogOptions {
+            level: Level::Debug,
+            days: vec![6],
+        });
+        assert!(enabled(Level::Debug));
+        assert!(!enabled(Level::Trace));
+        assert!(with_day(6, || enabled(Level::Debug)));
+        assert!(!with_day(7, || enabled(Level::Warn)));
+        init(&LogOptions::default());
+        assert!(with_day(7, || enabled(Level::Warn)));
+        assert!(!enabled(Level::Debug));
+    }
+}
@@ -1,48 +1,23 @@
-use std::io::{self, stdin, Write};
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
+use aoc::cli::{self, Failure};
+use aoc::commands;
+use aoc::config::Config;
+use aoc::log;
+use std::process::ExitCode;
 
-fn main() -> io::Result<()> {
-    print!("Enter a day: ");
-    io::stdout().flush()?;
-    let mut buffer = String::new();
-    let stdin = stdin();
-    stdin.read_line(&mut buffer)?;
-
-    match buffer.trim().parse::<u8>() {
-        Ok(1) => {
-            day1::day1()?;
-        }
-        Ok(2) => {
-            day2::day2()?;
-        }
-        Ok(3) => {
-            day3::day3()?;
-        }
-        Ok(4) => {
-            day4::day4()?;
-        }
-        Ok(5) => {
-            day5::day5()?;
-        }
-        Ok(6) => {
-            day6::day6()?;
-        }
-        Ok(7) => {
-            day7::day7()?;
-        }
-        Ok(8) => {
-            day8::day8()?;
-        }
-        _ => {
-            println!("No");
+fn main() -> ExitCode {
+    let result = cli::parse_log_args(std::env::args().skip(1))
+        .map_err(Failure::from)
+        .and_then(|(log_options, args)| {
+            log::init(&log_options);
+            let config = Config::load()?;
+            Ok((cli::parse_args_with(args, &config)?, config))
+        })
+        .and_then(|(command, config)| commands::execute(command, &config));
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("{failure}");
+            failure.exit_code()
         }
     }
-    Ok(())
 }
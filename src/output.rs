@@ -0,0 +1,219 @@
+use crate::runner::DayReport;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+/// One part of one day, flattened out of a `DayReport` for machine-readable output. A day that
+/// couldn't be parsed produces a record per part carrying the parse error.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub parse_time: Option<Duration>,
+    pub time: Option<Duration>,
+    pub error: Option<String>,
+}
+
+const FIELDS: [&str; 6] = ["day", "part", "answer", "parse_us", "time_us", "error"];
+// Every other field is a number
+const TEXT_FIELDS: [&str; 2] = ["answer", "error"];
+
+pub fn records(reports: &[(u8, DayReport)]) -> Vec<Record> {
+    let mut records = vec![];
+    for (day, report) in reports {
+        let parts = [(1, &report.part1), (2, &report.part2)];
+        match &report.parse {
+            Err(failure) => records.extend(parts.iter().map(|(part, _)| Record {
+                day: *day,
+                part: *part,
+                answer: None,
+                parse_time: None,
+                time: None,
+                error: Some(failure.to_string()),
+            })),
+            Ok(parse_time) => records.extend(parts.iter().filter_map(|(part, part_report)| {
+                part_report.as_ref().map(|part_report| Record {
+                    day: *day,
+                    part: *part,
+                    answer: part_report.answer.as_ref().ok().map(ToString::to_string),
+                    parse_time: Some(*parse_time),
+                    time: Some(part_report.time),
+                    error: part_report.answer.as_ref().err().cloned(),
+                })
+            })),
+        }
+    }
+    records
+}
+
+impl Record {
+    // Field values in the order of `FIELDS`, `None` where there is no value
+    fn values(&self) -> [Option<String>; 6] {
+        let micros = |time: Option<Duration>| time.map(|time| time.as_micros().to_string());
+        [
+            Some(self.day.to_string()),
+            Some(self.part.to_string()),
+            self.answer.clone(),
+            micros(self.parse_time),
+            micros(self.time),
+            self.error.clone(),
+        ]
+    }
+}
+
+/// A JSON array with one object per record. Answers are always strings so that large numbers
+/// survive parsers that read every number as a double.
+pub fn format_json(records: &[Record]) -> String {
+    let objects = records
+        .iter()
+        .map(|record| {
+            let fields = FIELDS
+                .iter()
+                .zip(record.values())
+                .map(|(name, value)| {
+                    let value = match value {
+                        None => "null".to_owned(),
+                        Some(value) if TEXT_FIELDS.contains(name) => json_string(&value),
+                        Some(value) => value,
+                    };
+                    format!("\"{name}\": {value}")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("  {{{fields}}}")
+        })
+        .collect::<Vec<_>>();
+    if objects.is_empty() {
+        "[]".to_owned()
+    } else {
+        format!("[\n{}\n]", objects.join(",\n"))
+    }
+}
+
+pub fn format_csv(records: &[Record]) -> String {
+    std::iter::once(FIELDS.join(","))
+        .chain(records.iter().map(|record| {
+            record
+                .values()
+                .map(|value| csv_field(&value.unwrap_or_default()))
+                .join(",")
+        }))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if character.is_control() => {
+                escaped.push_str(&format!("\\u{:04x}", character as u32));
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test_output {
+    use super::*;
+    use crate::cli::Failure;
+    use crate::runner::PartReport;
+    use crate::solution::Answer;
+
+    fn reports() -> Vec<(u8, DayReport)> {
+        vec![
+            (
+                5,
+                DayReport {
+                    parse: Ok(Duration::from_micros(400)),
+                    parse_alloc: None,
+                    part1: Some(PartReport {
+                        answer: Ok(Answer::Number(143)),
+                        time: Duration::from_micros(12),
+                        alloc: None,
+                    }),
+                    part2: Some(PartReport {
+                        answer: Err("not \"implemented\", yet".to_owned()),
+                        time: Duration::from_micros(3),
+                        alloc: None,
+                    }),
+                    phases: vec![],
+                },
+            ),
+            (
+                6,
+                DayReport {
+                    parse: Err(Failure::Solver("bad grid".to_owned())),
+                    parse_alloc: None,
+                    part1: None,
+                    part2: None,
+                    phases: vec![],
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_records() {
+        let records = records(&reports());
+        assert_eq!(records.len(), 4);
+        assert_eq!(
+            records[0],
+            Record {
+                day: 5,
+                part: 1,
+                answer: Some("143".to_owned()),
+                parse_time: Some(Duration::from_micros(400)),
+                time: Some(Duration::from_micros(12)),
+                error: None,
+            }
+        );
+        assert_eq!(records[3].error, Some("Solver failed: bad grid".to_owned()));
+    }
+
+    #[test]
+    fn test_format_json() {
+        assert_eq!(format_json(&[]), "[]");
+        assert_eq!(
+            format_json(&records(&reports())[..2]),
+            "[\n  {\"day\": 5, \"part\": 1, \"answer\": \"143\", \"parse_us\": 400, \"time_us\": 12, \"error\": null},\n  \
+             {\"day\": 5, \"part\": 2, \"answer\": null, \"parse_us\": 400, \"time_us\": 3, \"error\": \"not \\\"implemented\\\", yet\"}\n]"
+        );
+    }
+
+    #[test]
+    fn test_format_csv() {
+        assert_eq!(
+            format_csv(&records(&reports())),
+            "day,part,answer,parse_us,time_us,error\n\
+             5,1,143,400,12,\n\
+             5,2,,400,3,\"not \"\"implemented\"\", yet\"\n\
+             6,1,,,,Solver failed: bad grid\n\
+             6,2,,,,Solver failed: bad grid"
+        );
+    }
+}
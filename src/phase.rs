@@ -0,0 +1,193 @@
+use crate::runner::format_duration;
+use std::cell::RefCell;
+use std::mem;
+use std::time::{Duration, Instant};
+
+/// A timed stretch of a day's run. Phases with the same name under the same parent are merged,
+/// so a phase inside a loop shows up once with its total time and how often it ran.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Phase {
+    pub name: String,
+    pub time: Duration,
+    pub count: usize,
+    pub children: Vec<Phase>,
+}
+
+/// The phase the runner puts around every read of an input
+pub const IO: &str = "io";
+
+thread_local! {
+    // The phases being recorded, innermost last. Empty when nothing is recording.
+    static STACK: RefCell<Vec<Vec<Phase>>> = const { RefCell::new(vec![]) };
+}
+
+// A frame pushed on the stack, popped when dropped so that a panic unwinding out of `record`
+// doesn't leave it behind for the phases recorded after it
+struct Frame;
+
+impl Frame {
+    fn push() -> Self {
+        STACK.with_borrow_mut(|stack| stack.push(vec![]));
+        Frame
+    }
+
+    fn pop(self) -> Vec<Phase> {
+        let phases = STACK.with_borrow_mut(|stack| stack.pop().unwrap_or_default());
+        mem::forget(self);
+        phases
+    }
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        STACK.with_borrow_mut(|stack| stack.pop());
+    }
+}
+
+/// Runs `f` and returns the phases timed inside it
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, Vec<Phase>) {
+    let frame = Frame::push();
+    let result = f();
+    (result, frame.pop())
+}
+
+/// Times `f` as a phase nested in whichever phase is running. Outside of `record` it just runs
+/// `f`, so solvers can mark their phases unconditionally.
+pub fn time<R>(name: &str, f: impl FnOnce() -> R) -> R {
+    if STACK.with_borrow(Vec::is_empty) {
+        return f();
+    }
+    let start = Instant::now();
+    let (result, children) = record(f);
+    let time = start.elapsed();
+    STACK.with_borrow_mut(|stack| {
+        let siblings = stack.last_mut().expect("recording");
+        match siblings.iter_mut().find(|phase| phase.name == name) {
+            Some(phase) => {
+                phase.time += time;
+                phase.count += 1;
+                merge(&mut phase.children, children);
+            }
+            None => siblings.push(Phase {
+                name: name.to_owned(),
+                time,
+                count: 1,
+                children,
+            }),
+        }
+    });
+    result
+}
+
+fn merge(into: &mut Vec<Phase>, phases: Vec<Phase>) {
+    for phase in phases {
+        match into.iter_mut().find(|existing| existing.name == phase.name) {
+            Some(existing) => {
+                existing.time += phase.time;
+                existing.count += phase.count;
+                merge(&mut existing.children, phase.children);
+            }
+            None => into.push(phase),
+        }
+    }
+}
+
+/// How many times a phase of that name ran, at any depth
+pub fn count(phases: &[Phase], name: &str) -> usize {
+    phases
+        .iter()
+        .map(|phase| {
+            let own = if phase.name == name { phase.count } else { 0 };
+            own + count(&phase.children, name)
+        })
+        .sum()
+}
+
+/// One indented line per phase, with the run count when it ran more than once
+pub fn format_tree(phases: &[Phase]) -> String {
+    fn lines(phases: &[Phase], depth: usize, out: &mut Vec<(String, String)>) {
+        for phase in phases {
+            let mut time = format_duration(phase.time);
+            if phase.count > 1 {
+                time.push_str(&format!(" ({} runs)", phase.count));
+            }
+            out.push((format!("{}{}", "  ".repeat(depth), phase.name), time));
+            lines(&phase.children, depth + 1, out);
+        }
+    }
+    let mut out = vec![];
+    lines(phases, 0, &mut out);
+    let width = out
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    out.iter()
+        .map(|(name, time)| format!("{name:<width$}  {time}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test_phase {
+    use super::*;
+    use std::panic;
+
+    #[test]
+    fn test_record() {
+        assert_eq!(time("outside", || 1), 1);
+        let (answer, phases) = record(|| {
+            time("parse", || time(IO, || ()));
+            time("part 1", || {
+                for _ in 0..3 {
+                    time("walk", || ());
+                }
+                2
+            })
+        });
+        assert_eq!(answer, 2);
+        let names = |phases: &[Phase]| {
+            phases
+                .iter()
+                .map(|phase| (phase.name.clone(), phase.count))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(&phases),
+            [("parse".to_owned(), 1), ("part 1".to_owned(), 1)]
+        );
+        assert_eq!(names(&phases[0].children), [(IO.to_owned(), 1)]);
+        assert_eq!(names(&phases[1].children), [("walk".to_owned(), 3)]);
+        assert_eq!(count(&phases, IO), 1);
+    }
+
+    #[test]
+    fn test_panic() {
+        let (caught, phases) = record(|| {
+            time("part 1", || ());
+            panic::catch_unwind(|| time("part 2", || record(|| panic!("no answer"))))
+        });
+        assert!(caught.is_err());
+        assert_eq!(phases.len(), 1);
+        assert!(STACK.with_borrow(Vec::is_empty));
+        assert_eq!(time("outside", || 3), 3);
+    }
+
+    #[test]
+    fn test_format_tree() {
+        let phase = |name: &str, micros, count, children| Phase {
+            name: name.to_owned(),
+            time: Duration::from_micros(micros),
+            count,
+            children,
+        };
+        let phases = vec![
+            phase("parse", 40, 1, vec![phase(IO, 12, 1, vec![])]),
+            phase("part 2", 900, 1, vec![phase("walk", 850, 130, vec![])]),
+        ];
+        assert_eq!(
+            format_tree(&phases),
+            "parse   40µs\n  io    12µs\npart 2  900µs\n  walk  850µs (130 runs)"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use crate::cli::{Failure, Part, RunOptions};
+use crate::extract::insert_answers;
+use crate::input::INPUT_FILE;
+use crate::runner;
+use crate::solution::DynSolution;
+use crate::submit::Submissions;
+use crate::verify::{Expected, ExpectedAnswers};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// What recording did for one part
+#[derive(Debug, PartialEq, Eq)]
+pub enum Recorded {
+    /// A digest of the answer was written
+    Written,
+    /// The answers file already held a digest of it
+    Unchanged,
+    /// The site hasn't accepted an answer for the part yet
+    NotAccepted,
+}
+
+impl fmt::Display for Recorded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recorded::Written => write!(f, "recorded"),
+            Recorded::Unchanged => write!(f, "already recorded"),
+            Recorded::NotAccepted => write!(f, "no accepted answer yet, skipped"),
+        }
+    }
+}
+
+/// Runs the day on its input and writes a salted digest of each answer the site has accepted to
+/// the answers file. The answer has to be the one that was accepted, so a solver that has broken
+/// since can't record a wrong one. An answer written out in the file is replaced by its digest.
+pub fn record(
+    solution: &dyn DynSolution,
+    options: &RunOptions,
+    year: u16,
+    submissions: &Submissions,
+    answers_path: &Path,
+) -> Result<Vec<(Part, Recorded)>, Failure> {
+    let file_error =
+        |error: io::Error| Failure::Verification(format!("{}: {error}", answers_path.display()));
+    let mut answers = match fs::read_to_string(answers_path) {
+        Ok(answers) => answers,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(file_error(error)),
+    };
+    let expected = ExpectedAnswers::parse(&answers)
+        .map_err(|error| Failure::Verification(format!("{}: {error}", answers_path.display())))?;
+
+    let day = solution.day();
+    let options = RunOptions {
+        part: None,
+        input: None,
+        ..options.clone()
+    };
+    // Solver panics come back as failures, without a backtrace on top
+    let report = runner::quietly(|| runner::run(solution, &options));
+    report.parse?;
+    let mut recorded = vec![];
+    for (part, part_report) in [(Part::One, report.part1), (Part::Two, report.part2)] {
+        // A part the site hasn't accepted is skipped before its answer is looked at, so that an
+        // unsolved part doesn't keep the other from being recorded
+        let Some(accepted) = submissions.accepted(year, day, part) else {
+            recorded.push((part, Recorded::NotAccepted));
+            continue;
+        };
+        let answer = part_report
+            .expect("both parts were asked for")
+            .answer
+            .map_err(Failure::Solver)?
+            .to_string();
+        if accepted != answer {
+            return Err(Failure::Verification(format!(
+                "part {} gives {answer}, but the site accepted {accepted}",
+                part.number()
+            )));
+        }
+        match expected.get(day, INPUT_FILE, part) {
+            Some(existing) if !existing.matches(&answer) => {
+                return Err(Failure::Verification(format!(
+                    "{} expects {existing} for part {}, but the site accepted {answer}",
+                    answers_path.display(),
+                    part.number()
+                )));
+            }
+            Some(Expected::Digest { .. }) => recorded.push((part, Recorded::Unchanged)),
+            _ => {
+                let line = format!(
+                    "{day} {INPUT_FILE} {} {}",
+                    part.number(),
+                    Expected::hashed(&answer)
+                );
+                answers = set_answer(&answers, day, INPUT_FILE, part, line);
+                recorded.push((part, Recorded::Written));
+            }
+        }
+    }
+    if recorded
+        .iter()
+        .any(|(_, outcome)| *outcome == Recorded::Written)
+    {
+        fs::write(answers_path, answers).map_err(file_error)?;
+    }
+    Ok(recorded)
+}
+
+// Replaces the line for the day, input and part, or adds one if there is none
+fn set_answer(answers: &str, day: u8, input: &str, part: Part, line: String) -> String {
+    let key = [day.to_string(), input.to_owned(), part.number().to_string()];
+    let mut lines = answers.lines().map(str::to_owned).collect::<Vec<_>>();
+    let existing = lines.iter().position(|existing| {
+        existing
+            .split_whitespace()
+            .take(3)
+            .eq(key.iter().map(String::as_str))
+    });
+    match existing {
+        Some(index) => {
+            lines[index] = line;
+            lines.join("\n") + "\n"
+        }
+        None => insert_answers(answers, day, &[line]),
+    }
+}
+
+#[cfg(test)]
+mod test_record {
+    use super::*;
+    use crate::error;
+    use crate::solution::{Answer, Solution};
+    use std::env;
+    use std::io::BufRead;
+
+    #[test]
+    fn test_set_answer() {
+        let answers = "6 example.txt 1 41\n6 input.txt 1 4602\n\n7 input.txt 1 3\n";
+        assert_eq!(
+            set_answer(
+                answers,
+                6,
+                "input.txt",
+                Part::One,
+                "6 input.txt 1 x".to_owned()
+            ),
+            "6 example.txt 1 41\n6 input.txt 1 x\n\n7 input.txt 1 3\n"
+        );
+        assert_eq!(
+            set_answer(
+                answers,
+                6,
+                "input.txt",
+                Part::Two,
+                "6 input.txt 2 y".to_owned()
+            ),
+            "6 example.txt 1 41\n6 input.txt 1 4602\n6 input.txt 2 y\n\n7 input.txt 1 3\n"
+        );
+    }
+
+    struct Fixed;
+
+    impl Solution for Fixed {
+        type Input = ();
+        const DAY: u8 = 1;
+
+        fn parse(&self, _reader: &mut dyn BufRead) -> error::Result<()> {
+            Ok(())
+        }
+        fn part1(&self, _input: &()) -> error::Result<Answer> {
+            Ok(41u32.into())
+        }
+        fn part2(&self, _input: &()) -> error::Result<Answer> {
+            Ok(6u32.into())
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let dir = env::temp_dir().join(format!("aoc-record-{}", std::process::id()));
+        fs::create_dir_all(dir.join("day1")).unwrap();
+        fs::write(dir.join("day1/input.txt"), "").unwrap();
+        let answers = dir.join("answers.txt");
+        fs::write(&answers, "1 example.txt 1 11\n1 input.txt 1 41\n").unwrap();
+        let options = RunOptions {
+            input_dir: Some(dir.clone()),
+            ..RunOptions::default()
+        };
+
+        let submissions = Submissions::parse("2024 1 1 40 too-low\n2024 1 1 41 correct\n");
+        assert_eq!(
+            record(&Fixed, &options, 2024, &submissions, &answers).unwrap(),
+            [
+                (Part::One, Recorded::Written),
+                (Part::Two, Recorded::NotAccepted)
+            ]
+        );
+        let contents = fs::read_to_string(&answers).unwrap();
+        assert!(contents.starts_with("1 example.txt 1 11\n1 input.txt 1 sha256:"));
+        assert!(!contents.contains(" 41"));
+        let expected = ExpectedAnswers::parse(&contents).unwrap();
+        assert!(expected
+            .get(1, INPUT_FILE, Part::One)
+            .unwrap()
+            .matches("41"));
+
+        let submissions = Submissions::parse("2024 1 1 41 correct\n2024 1 2 6 correct\n");
+        assert_eq!(
+            record(&Fixed, &options, 2024, &submissions, &answers).unwrap(),
+            [
+                (Part::One, Recorded::Unchanged),
+                (Part::Two, Recorded::Written)
+            ]
+        );
+
+        let submissions = Submissions::parse("2024 1 1 42 correct\n");
+        assert!(matches!(
+            record(&Fixed, &options, 2024, &submissions, &answers),
+            Err(Failure::Verification(_))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+use crate::cli::{self, Part};
+use crate::input::{InputResolver, INPUT_FILE};
+use crate::runner::{self, format_duration};
+use crate::solution::{self, DynSolution};
+use std::any::Any;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const HELP: &str = "Commands:
+  day N         select a day and parse its input
+  input NAME    parse another input, e.g. example.txt or a path
+  part 1|2      run a part against the parsed input
+  run           run both parts
+  show          print the parsed input, if the day can display it
+  time          toggle timings
+  reload        re-read and re-parse the current input
+  help          show this message
+  quit          leave";
+
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// The state of an interactive session. The parsed input is kept between commands so that parts
+/// can be re-run without reading the file again.
+pub struct Session {
+    solutions: &'static [&'static dyn DynSolution],
+    resolver: InputResolver,
+    solution: Option<&'static dyn DynSolution>,
+    input: String,
+    parsed: Option<Box<dyn Any>>,
+    timing: bool,
+}
+
+impl Session {
+    pub fn new(solutions: &'static [&'static dyn DynSolution], resolver: InputResolver) -> Self {
+        Session {
+            solutions,
+            resolver,
+            solution: None,
+            input: INPUT_FILE.to_owned(),
+            parsed: None,
+            timing: false,
+        }
+    }
+
+    pub fn execute<W: Write>(&mut self, line: &str, out: &mut W) -> io::Result<Flow> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let argument = words.next();
+        match (command, argument) {
+            ("", _) => {}
+            ("quit" | "exit" | "q", _) => return Ok(Flow::Quit),
+            ("help" | "?", _) => writeln!(out, "{HELP}")?,
+            ("day", Some(day)) => match cli::parse_day(day)
+                .ok()
+                .and_then(|day| solution::find(self.solutions, day))
+            {
+                Some(solution) => {
+                    self.solution = Some(solution);
+                    self.input = INPUT_FILE.to_owned();
+                    self.load(out)?;
+                }
+                None => writeln!(out, "Unknown day: {day}")?,
+            },
+            ("input", Some(input)) => {
+                self.input = input.to_owned();
+                self.load(out)?;
+            }
+            ("reload", None) => self.load(out)?,
+            ("part", Some("1")) => self.run_part(Part::One, out)?,
+            ("part", Some("2")) => self.run_part(Part::Two, out)?,
+            ("run", None) => {
+                self.run_part(Part::One, out)?;
+                self.run_part(Part::Two, out)?;
+            }
+            ("show", None) => match (self.solution, &self.parsed) {
+                (Some(solution), Some(parsed)) => match solution.show(parsed.as_ref()) {
+                    Some(shown) => writeln!(out, "{shown}")?,
+                    None => writeln!(out, "Day {} can't display its input", solution.day())?,
+                },
+                _ => writeln!(out, "Nothing parsed yet, pick a day first")?,
+            },
+            ("time", None) => {
+                self.timing = !self.timing;
+                writeln!(out, "Timing {}", if self.timing { "on" } else { "off" })?;
+            }
+            _ => writeln!(out, "Unknown command: {}, try help", line.trim())?,
+        }
+        Ok(Flow::Continue)
+    }
+
+    // A name containing a path separator is used as is, anything else is looked up in the day's
+    // input directory
+    fn input_path(&self, day: u8) -> PathBuf {
+        if self.input.contains(std::path::MAIN_SEPARATOR) || self.input.contains('/') {
+            PathBuf::from(&self.input)
+        } else {
+            self.resolver.path(day, &self.input)
+        }
+    }
+
+    fn load<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        self.parsed = None;
+        let Some(solution) = self.solution else {
+            return writeln!(out, "Pick a day first");
+        };
+        let path = self.input_path(solution.day());
+        let start = Instant::now();
+        match runner::parse(solution, &path) {
+            Ok(parsed) => {
+                let time = start.elapsed();
+                self.parsed = Some(parsed);
+                write!(out, "Day {}: parsed {}", solution.day(), self.input)?;
+                self.write_time(out, time)
+            }
+            Err(failure) => writeln!(out, "{failure}"),
+        }
+    }
+
+    fn run_part<W: Write>(&self, part: Part, out: &mut W) -> io::Result<()> {
+        let (Some(solution), Some(parsed)) = (self.solution, &self.parsed) else {
+            return writeln!(out, "Nothing parsed yet, pick a day first");
+        };
+        let start = Instant::now();
+        let answer = runner::run_part(solution, parsed.as_ref(), part);
+        let time = start.elapsed();
+        match answer {
+            Ok(answer) => write!(out, "Part{}: {answer}", part.number())?,
+            Err(message) => write!(out, "Part{} failed: {message}", part.number())?,
+        }
+        self.write_time(out, time)
+    }
+
+    fn write_time<W: Write>(&self, out: &mut W, time: Duration) -> io::Result<()> {
+        if self.timing {
+            writeln!(out, " in {}", format_duration(time))
+        } else {
+            writeln!(out)
+        }
+    }
+
+    fn prompt(&self) -> String {
+        match self.solution {
+            Some(solution) => format!("day{}:{}> ", solution.day(), self.input),
+            None => "aoc> ".to_owned(),
+        }
+    }
+}
+
+/// Reads commands until `quit` or the end of the input
+pub fn run<R: BufRead, W: Write>(mut session: Session, input: R, mut out: W) -> io::Result<()> {
+    writeln!(out, "Type help for a list of commands")?;
+    let mut lines = input.lines();
+    loop {
+        write!(out, "{}", session.prompt())?;
+        out.flush()?;
+        let Some(line) = lines.next() else {
+            writeln!(out)?;
+            return Ok(());
+        };
+        if let Flow::Quit = session.execute(&line?, &mut out)? {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod session {
+    use super::*;
+    use crate::error;
+    use crate::solution::{Answer, Solution};
+
+    struct Counter;
+
+    impl Solution for Counter {
+        type Input = Vec<String>;
+        const DAY: u8 = 3;
+
+        fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {
+            Ok(reader.lines().collect::<io::Result<_>>()?)
+        }
+        fn part1(&self, input: &Self::Input) -> error::Result<Answer> {
+            Ok(input.len().into())
+        }
+        fn part2(&self, _input: &Self::Input) -> error::Result<Answer> {
+            unimplemented!()
+        }
+        fn show(&self, input: &Self::Input) -> Option<String> {
+            Some(input.join("|"))
+        }
+    }
+
+    static SOLUTIONS: &[&dyn DynSolution] = &[&Counter];
+
+    fn execute_all(commands: &[&str]) -> String {
+        let mut session = Session::new(SOLUTIONS, InputResolver::source_tree());
+        let mut out = vec![];
+        for command in commands {
+            session.execute(command, &mut out).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_session() {
+        assert_eq!(
+            execute_all(&["day 3", "input example.txt", "part 1", "show", "part 2"]),
+            "Day 3: parsed input.txt\n\
+             Day 3: parsed example.txt\n\
+             Part1: 1\n\
+             xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))\n\
+             Part2 failed: not implemented\n"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            execute_all(&["part 1", "day 9", "dance"]),
+            "Nothing parsed yet, pick a day first\nUnknown day: 9\nUnknown command: dance, try help\n"
+        );
+        assert!(execute_all(&["day 3", "input missing.txt"]).contains("Could not read"));
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut session = Session::new(SOLUTIONS, InputResolver::source_tree());
+        assert!(matches!(
+            session.execute("quit", &mut vec![]).unwrap(),
+            Flow::Quit
+        ));
+    }
+}
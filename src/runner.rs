@@ -0,0 +1,634 @@
+use crate::alloc::{self, AllocStats};
+use crate::cancel::{self, CancellationToken};
+use crate::cli::{Failure, Part, RunOptions};
+use crate::error::Error;
+use crate::input;
+use crate::log;
+use crate::phase::{self, Phase};
+use crate::solution::{Answer, DynSolution};
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt::Display;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub answer: Result<Answer, String>,
+    pub time: Duration,
+    /// Only counted when built with the `alloc-stats` feature
+    pub alloc: Option<AllocStats>,
+}
+
+/// The answers and wall-clock timings of a single day. A part that wasn't run is `None`.
+#[derive(Debug)]
+pub struct DayReport {
+    pub parse: Result<Duration, Failure>,
+    pub parse_alloc: Option<AllocStats>,
+    pub part1: Option<PartReport>,
+    pub part2: Option<PartReport>,
+    /// Where the time went, as recorded by `phase::time`
+    pub phases: Vec<Phase>,
+}
+
+impl DayReport {
+    // Collapses the report down to the first thing that went wrong, if anything did
+    pub fn into_result(self) -> Result<(), Failure> {
+        self.parse?;
+        for part in [self.part1, self.part2].into_iter().flatten() {
+            part.answer.map_err(|message| {
+                if message == TIMEOUT {
+                    Failure::Timeout
+                } else {
+                    Failure::Solver(message)
+                }
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// What a part that ran past `--timeout` reports instead of its answer
+pub const TIMEOUT: &str = "TIMEOUT";
+
+// Parses the input once, then runs whichever parts were asked for against it. Panics are caught so
+// that one broken part can't take the rest of a run down with it. With a timeout, the day is
+// cancelled once it has been running for that long and whatever didn't finish reports TIMEOUT.
+pub fn run(solution: &dyn DynSolution, options: &RunOptions) -> DayReport {
+    log::with_day(solution.day(), || {
+        let (mut report, phases) = phase::record(|| run_day(solution, options));
+        let reads = phase::count(&phases, phase::IO);
+        if reads > 1 {
+            log::warning!("read its input {reads} times");
+        }
+        report.phases = phases;
+        report
+    })
+}
+
+fn run_day(solution: &dyn DynSolution, options: &RunOptions) -> DayReport {
+    let start = Instant::now();
+    let token = options
+        .timeout
+        .map_or_else(CancellationToken::new, |timeout| {
+            CancellationToken::with_deadline(start + timeout)
+        });
+    let path = options.input_path(solution.day());
+    cancel::scope(&token, || {
+        let contents = match read(&path) {
+            Ok(contents) => contents,
+            Err(failure) => return DayReport::failed(failure),
+        };
+        log::debug!("parsing {}", path.display());
+        let parse_start = Instant::now();
+        let (parsed, parse_alloc) = alloc::measure(|| parse_contents(solution, &path, &contents));
+        let parse_time = parse_start.elapsed();
+        if token.is_cancelled() {
+            log::warning!("timed out while parsing");
+            return DayReport::failed(Failure::Timeout);
+        }
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(failure) => return DayReport::failed(failure),
+        };
+        log::debug!("parsed in {}", format_duration(parse_time));
+
+        let run_part = |part: Part| {
+            options.wants(part).then(|| {
+                if token.is_cancelled() {
+                    return PartReport {
+                        answer: Err(TIMEOUT.to_owned()),
+                        time: Duration::ZERO,
+                        alloc: None,
+                    };
+                }
+                log::debug!("running part {}", part.number());
+                let start = Instant::now();
+                let (answer, alloc) = phase::time(&format!("part {}", part.number()), || {
+                    alloc::measure(|| run_part(solution, parsed.as_ref(), part))
+                });
+                let time = start.elapsed();
+                // A cancelled solver returns early with a meaningless answer
+                let answer = if token.is_cancelled() {
+                    log::warning!("part {} timed out", part.number());
+                    Err(TIMEOUT.to_owned())
+                } else {
+                    log::debug!(
+                        "part {} finished in {}",
+                        part.number(),
+                        format_duration(time)
+                    );
+                    answer
+                };
+                PartReport {
+                    answer,
+                    time,
+                    alloc,
+                }
+            })
+        };
+        DayReport {
+            parse: Ok(parse_time),
+            parse_alloc,
+            part1: run_part(Part::One),
+            part2: run_part(Part::Two),
+            phases: vec![],
+        }
+    })
+}
+
+/// Opens and parses an input. A missing file, malformed input and a panicking parser each become
+/// the matching `Failure`, with parse errors pointing into the file.
+pub fn parse(solution: &dyn DynSolution, path: &Path) -> Result<Box<dyn Any>, Failure> {
+    parse_contents(solution, path, &read(path)?)
+}
+
+// Reads an input, telling a missing file apart from one that could not be read
+fn read(path: &Path) -> Result<Vec<u8>, Failure> {
+    input::read(path).map_err(|error| match error.kind() {
+        io::ErrorKind::NotFound => Failure::MissingInput(path.to_owned(), error),
+        _ => Failure::Solver(format!("{}: {error}", path.display())),
+    })
+}
+
+// Parses an input already read from `path`, which is only used to point at the file
+fn parse_contents(
+    solution: &dyn DynSolution,
+    path: &Path,
+    contents: &[u8],
+) -> Result<Box<dyn Any>, Failure> {
+    match phase::time("parse", || catch(|| solution.parse(&mut &contents[..]))) {
+        Ok(Ok(parsed)) => Ok(parsed),
+        Ok(Err(Error::Io(error))) if error.kind() == io::ErrorKind::NotFound => {
+            Err(Failure::MissingInput(path.to_owned(), error))
+        }
+        Ok(Err(error)) => Err(Failure::Solver(error.in_file(path).to_string())),
+        Err(message) => Err(Failure::Solver(message)),
+    }
+}
+
+// Runs a single part, with its errors and panics both reduced to a message
+pub fn run_part(
+    solution: &dyn DynSolution,
+    parsed: &dyn Any,
+    part: Part,
+) -> Result<Answer, String> {
+    catch(|| match part {
+        Part::One => solution.part1(parsed),
+        Part::Two => solution.part2(parsed),
+    })
+    .and_then(|answer| answer.map_err(|error| error.to_string()))
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+// Puts the previous quiet flag back even if the closure panics
+struct Restore(bool);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        QUIET.set(self.0);
+    }
+}
+
+/// Runs `f` without printing the panics raised on this thread, for callers that report the panics
+/// they catch themselves. Panics on other threads are printed as usual.
+pub fn quietly<R>(f: impl FnOnce() -> R) -> R {
+    // Swapping the hook for every call would race between threads, so a single hook is installed
+    // that asks the panicking thread whether to stay quiet
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.try_with(Cell::get).unwrap_or(false) {
+                hook(info);
+            }
+        }));
+    });
+    let _restore = Restore(QUIET.replace(true));
+    f()
+}
+
+/// Runs every solution on a pool of `jobs` threads and returns the reports in the order of
+/// `solutions`. Each day is parsed and solved on a single thread, so its timings cover only its own
+/// work, although days running side by side still compete for the CPU.
+pub fn run_all(
+    solutions: &[&dyn DynSolution],
+    options: &RunOptions,
+    jobs: usize,
+) -> Vec<(u8, DayReport)> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, solutions.len().max(1)) {
+            let sender = sender.clone();
+            let next = &next;
+            // Printing panics would also interleave the output of days running side by side
+            scope.spawn(move || {
+                quietly(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(solution) = solutions.get(index) else {
+                        break;
+                    };
+                    // The receiver outlives the scope, so sending can't fail
+                    let _ = sender.send((index, (solution.day(), run(*solution, options))));
+                })
+            });
+        }
+    });
+    drop(sender);
+    let mut reports = receiver.into_iter().collect::<Vec<_>>();
+    reports.sort_by_key(|(index, _)| *index);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+impl DayReport {
+    fn failed(failure: Failure) -> Self {
+        DayReport {
+            parse: Err(failure),
+            parse_alloc: None,
+            part1: None,
+            part2: None,
+            phases: vec![],
+        }
+    }
+}
+
+pub fn catch<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "panicked".to_owned()
+        }
+    })
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1_000 {
+        format!("{micros}µs")
+    } else if micros < 1_000_000 {
+        format!("{:.2}ms", duration.as_secs_f64() * 1_000.0)
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+/// Lays out one row per day with both answers and the parse, part 1 and part 2 timings. Memory
+/// columns are added when allocations were counted.
+pub fn format_table(reports: &[(u8, DayReport)]) -> String {
+    let memory = reports.iter().any(|(_, report)| {
+        report.parse_alloc.is_some()
+            || [&report.part1, &report.part2]
+                .into_iter()
+                .flatten()
+                .any(|part| part.alloc.is_some())
+    });
+    let mut header = vec![
+        "Day",
+        "Part 1",
+        "Part 2",
+        "Parse",
+        "Part 1 time",
+        "Part 2 time",
+    ];
+    if memory {
+        header.extend(["Parse memory", "Part 1 memory", "Part 2 memory"]);
+    }
+    let header = header.into_iter().map(String::from).collect::<Vec<_>>();
+    let rows = reports
+        .iter()
+        .map(|(day, report)| {
+            let (parse, part1, part2) = match &report.parse {
+                Ok(time) => (
+                    format_duration(*time),
+                    part_cells(&report.part1),
+                    part_cells(&report.part2),
+                ),
+                Err(failure) => (
+                    "-".to_owned(),
+                    (error_cell(&failure.to_string()), "-".to_owned()),
+                    ("-".to_owned(), "-".to_owned()),
+                ),
+            };
+            let mut row = vec![day.to_string(), part1.0, part2.0, parse, part1.1, part2.1];
+            if memory && report.parse.is_ok() {
+                let part_memory = |part: &Option<PartReport>| {
+                    memory_cell(part.as_ref().and_then(|part| part.alloc))
+                };
+                row.extend([
+                    memory_cell(report.parse_alloc),
+                    part_memory(&report.part1),
+                    part_memory(&report.part2),
+                ]);
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+    format_rows(&header, &rows)
+}
+
+fn memory_cell(stats: Option<AllocStats>) -> String {
+    stats.map_or_else(|| "-".to_owned(), |stats| stats.to_string())
+}
+
+/// Left-aligns every column. A row shorter than the header (usually one carrying an error) spills
+/// its last cell over the remaining columns instead of widening the column it starts in.
+pub fn format_rows(header: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths = header
+        .iter()
+        .map(|cell| cell.chars().count())
+        .collect::<Vec<_>>();
+    for row in rows.iter() {
+        let sized_cells = if row.len() < header.len() {
+            row.len().saturating_sub(1)
+        } else {
+            row.len()
+        };
+        for (width, cell) in widths.iter_mut().zip(row.iter().take(sized_cells)) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    std::iter::once(header)
+        .chain(rows.iter().map(Vec::as_slice))
+        .map(|row| format_row(row, &widths))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// (answer, time) cells for one part
+fn part_cells(part: &Option<PartReport>) -> (String, String) {
+    match part {
+        None => ("-".to_owned(), "-".to_owned()),
+        Some(PartReport {
+            answer: Ok(answer),
+            time,
+            ..
+        }) => (answer.to_string(), format_duration(*time)),
+        Some(PartReport {
+            answer: Err(message),
+            time,
+            ..
+        }) => (error_cell(message), format_duration(*time)),
+    }
+}
+
+fn error_cell(message: &str) -> String {
+    if message == TIMEOUT {
+        TIMEOUT.to_owned()
+    } else {
+        format!("ERROR: {message}")
+    }
+}
+
+fn format_row<S: Display>(row: &[S], widths: &[usize]) -> String {
+    row.iter()
+        .zip(widths.iter())
+        .map(|(cell, width)| format!("{:<width$}", cell.to_string(), width = *width))
+        .collect::<Vec<_>>()
+        .join("  ")
+        .trim_end()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod test_run {
+    use super::*;
+    use crate::error;
+    use crate::input::{InputResolver, EXAMPLE_FILE};
+    use crate::solution::Solution;
+    use std::io::BufRead;
+
+    struct Broken;
+
+    impl Solution for Broken {
+        type Input = u32;
+        const DAY: u8 = 1;
+
+        fn parse(&self, _reader: &mut dyn BufRead) -> error::Result<u32> {
+            Ok(21)
+        }
+        fn part1(&self, input: &u32) -> error::Result<Answer> {
+            Ok((input * 2).into())
+        }
+        fn part2(&self, _input: &u32) -> error::Result<Answer> {
+            unimplemented!()
+        }
+    }
+
+    struct Missing;
+
+    impl Solution for Missing {
+        type Input = u32;
+        const DAY: u8 = 2;
+
+        fn parse(&self, _reader: &mut dyn BufRead) -> error::Result<u32> {
+            Err(io::Error::from(io::ErrorKind::NotFound).into())
+        }
+        fn part1(&self, input: &u32) -> error::Result<Answer> {
+            Ok((*input).into())
+        }
+        fn part2(&self, input: &u32) -> error::Result<Answer> {
+            Ok((*input).into())
+        }
+    }
+
+    struct Malformed;
+
+    impl Solution for Malformed {
+        type Input = u32;
+        const DAY: u8 = 3;
+
+        fn parse(&self, _reader: &mut dyn BufRead) -> error::Result<u32> {
+            Err(Error::parse(2, 5, "expected a number"))
+        }
+        fn part1(&self, _input: &u32) -> error::Result<Answer> {
+            Err(Error::NoSolution("nothing to count".to_owned()))
+        }
+        fn part2(&self, _input: &u32) -> error::Result<Answer> {
+            unimplemented!()
+        }
+    }
+
+    struct Endless;
+
+    impl Solution for Endless {
+        type Input = u32;
+        const DAY: u8 = 4;
+
+        fn parse(&self, _reader: &mut dyn BufRead) -> error::Result<u32> {
+            Ok(0)
+        }
+        fn part1(&self, input: &u32) -> error::Result<Answer> {
+            while !cancel::cancelled() {}
+            Ok((*input).into())
+        }
+        fn part2(&self, input: &u32) -> error::Result<Answer> {
+            Ok((*input).into())
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let path = InputResolver::source_tree().path(Malformed::DAY, EXAMPLE_FILE);
+        let failure = parse(&Malformed, &path).err().unwrap();
+        assert!(failure
+            .to_string()
+            .ends_with("src/day3/example.txt:2:5: expected a number"));
+        assert_eq!(
+            run_part(&Malformed, &0u32, Part::One),
+            Err("no solution: nothing to count".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_both_parts() {
+        let report = run(&Broken, &RunOptions::default());
+        assert!(report.parse.is_ok());
+        assert_eq!(report.part1.unwrap().answer, Ok(Answer::Number(42)));
+        assert_eq!(
+            report.part2.unwrap().answer,
+            Err("not implemented".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_run_all_keeps_order() {
+        let solutions: [&dyn DynSolution; 3] = [&Broken, &Missing, &Broken];
+        for jobs in [1, 2, 8] {
+            let reports = run_all(&solutions, &RunOptions::default(), jobs);
+            let days = reports.iter().map(|(day, _)| *day).collect::<Vec<_>>();
+            assert_eq!(days, vec![1, 2, 1]);
+            assert!(reports[1].1.parse.is_err());
+        }
+    }
+
+    #[test]
+    fn test_single_part() {
+        let options = RunOptions {
+            part: Some(Part::One),
+            ..RunOptions::default()
+        };
+        let report = run(&Broken, &options);
+        assert!(report.part1.is_some());
+        assert!(report.part2.is_none());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(10)),
+            ..RunOptions::default()
+        };
+        let report = run(&Endless, &options);
+        assert_eq!(report.part1.unwrap().answer, Err(TIMEOUT.to_owned()));
+        assert_eq!(report.part2.unwrap().answer, Err(TIMEOUT.to_owned()));
+        assert!(run(&Broken, &options).part1.unwrap().answer.is_ok());
+    }
+
+    #[test]
+    fn test_phases() {
+        let report = run(&Broken, &RunOptions::default());
+        let names = report
+            .phases
+            .iter()
+            .map(|phase| phase.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, [phase::IO, "parse", "part 1", "part 2"]);
+        assert_eq!(phase::count(&report.phases, phase::IO), 1);
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let report = run(&Missing, &RunOptions::default());
+        assert!(matches!(report.parse, Err(Failure::MissingInput(..))));
+        assert!(report.part1.is_none());
+    }
+
+    #[test]
+    fn test_quietly() {
+        assert!(catch(|| quietly(|| panic!("quiet"))).is_err());
+        assert!(!QUIET.get());
+
+        quietly(|| {
+            quietly(|| ());
+            assert!(QUIET.get());
+            // Another thread still prints its panics
+            assert!(!thread::spawn(|| QUIET.get()).join().unwrap());
+        });
+        assert!(!QUIET.get());
+    }
+}
+
+#[cfg(test)]
+mod test_format_table {
+    use super::*;
+
+    #[test]
+    fn test_format_table() {
+        let reports = vec![
+            (
+                1,
+                DayReport {
+                    parse: Ok(Duration::from_micros(12)),
+                    parse_alloc: None,
+                    part1: Some(PartReport {
+                        answer: Ok(Answer::Number(11)),
+                        time: Duration::from_micros(3),
+                        alloc: None,
+                    }),
+                    part2: None,
+                    phases: vec![],
+                },
+            ),
+            (
+                2,
+                DayReport::failed(Failure::Solver("bad input".to_owned())),
+            ),
+        ];
+        assert_eq!(
+            format_table(&reports),
+            "Day  Part 1                           Part 2  Parse  Part 1 time  Part 2 time\n\
+             1    11                               -       12µs   3µs          -\n\
+             2    ERROR: Solver failed: bad input  -       -      -            -"
+        );
+    }
+
+    #[test]
+    fn test_format_table_memory() {
+        let stats = AllocStats {
+            allocations: 3,
+            bytes: 2048,
+            peak: 1024,
+        };
+        let reports = vec![(
+            1,
+            DayReport {
+                parse: Ok(Duration::from_micros(12)),
+                parse_alloc: Some(stats),
+                part1: Some(PartReport {
+                    answer: Ok(Answer::Number(11)),
+                    time: Duration::from_micros(3),
+                    alloc: Some(AllocStats::default()),
+                }),
+                part2: None,
+                phases: vec![],
+            },
+        )];
+        assert_eq!(
+            format_table(&reports).lines().nth(1).unwrap(),
+            "1    11      -       12µs   3µs          -            \
+             3 allocs, 2.0KiB, peak 1.0KiB  0 allocs, 0B, peak 0B  -"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use crate::cli::Failure;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const REGISTRY_START: &str = "solution::days! {";
+
+// The module, with the solution and a failing example test
+fn mod_rs(day: u8) -> String {
+    format!(
+        "use crate::error;
+use crate::solution::{{Answer, Solution}};
+use std::io::BufRead;
+
+mod parser;
+mod puzzle;
+
+pub use parser::parser;
+pub use puzzle::Puzzle;
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Input = Puzzle;
+    const DAY: u8 = {day};
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input> {{
+        parser(reader)
+    }}
+    fn part1(&self, _puzzle: &Self::Input) -> error::Result<Answer> {{
+        unimplemented!()
+    }}
+    fn part2(&self, _puzzle: &Self::Input) -> error::Result<Answer> {{
+        unimplemented!()
+    }}
+    fn show(&self, puzzle: &Self::Input) -> Option<String> {{
+        Some(puzzle.to_string())
+    }}
+}}
+
+#[cfg(test)]
+mod test_solution {{
+    use super::Day{day};
+    use crate::input::example;
+    use crate::solution::{{Answer, Solution}};
+
+    #[test]
+    fn test_parts() {{
+        let input = Day{day}.parse(&mut example({day})).unwrap();
+        // The answer to the example in the puzzle text
+        assert_eq!(Day{day}.part1(&input).unwrap(), Answer::Number(0));
+    }}
+}}
+"
+    )
+}
+
+fn parser_rs(day: u8) -> String {
+    format!(
+        "use std::io::{{self, BufRead}};
+
+use super::Day{day};
+use crate::day{day}::puzzle::Puzzle;
+use crate::error;
+use crate::input::ParserInput;
+use crate::solution::Solution;
+
+pub fn parser<I: ParserInput>(input: I) -> error::Result<Puzzle> {{
+    let lines = input.open(Day{day}::DAY)?.lines().collect::<io::Result<Vec<_>>>()?;
+    Ok(Puzzle::new(lines))
+}}
+"
+    )
+}
+
+const PUZZLE_RS: &str = "use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Puzzle {
+    lines: Vec<String>,
+}
+
+impl Puzzle {
+    pub fn new(lines: Vec<String>) -> Self {
+        Self { lines }
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, \"{}\", self.lines.join(\"\\n\"))
+    }
+}
+";
+
+/// The files of a new day, relative to the source directory. The model is named `Puzzle` until
+/// the puzzle says what it really is.
+pub fn files(day: u8) -> Vec<(PathBuf, String)> {
+    let dir = PathBuf::from(format!("day{day}"));
+    vec![
+        (dir.join("mod.rs"), mod_rs(day)),
+        (dir.join("parser.rs"), parser_rs(day)),
+        (dir.join("puzzle.rs"), PUZZLE_RS.to_owned()),
+        (dir.join("example.txt"), String::new()),
+    ]
+}
+
+// Declares `pub mod dayN;` and adds `dayN => DayN,` to the `days!` list, keeping both sorted by
+// day
+pub fn register(lib: &str, day: u8) -> Result<String, String> {
+    let lib = list(lib, day)?;
+    Ok(declare(&lib, day))
+}
+
+// Puts `pub mod dayN;` before the first day module that comes after it, or after the last one. A
+// lib.rs without any goes right before the `days!` list.
+fn declare(lib: &str, day: u8) -> String {
+    let declared = |line: &str| {
+        line.strip_prefix("pub mod day")
+            .and_then(|rest| rest.strip_suffix(';'))
+            .and_then(|number| number.parse::<u8>().ok())
+    };
+    let mut lines = lib.lines().collect::<Vec<_>>();
+    let days = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| Some((index, declared(line)?)))
+        .collect::<Vec<_>>();
+    let index = match days.iter().find(|(_, declared)| *declared > day) {
+        Some((index, _)) => *index,
+        None => match days.last() {
+            Some((index, _)) => index + 1,
+            None => lines
+                .iter()
+                .position(|line| line.starts_with(REGISTRY_START))
+                .unwrap_or(lines.len()),
+        },
+    };
+    let module = format!("pub mod day{day};");
+    lines.insert(index, &module);
+    lines.join("\n") + "\n"
+}
+
+// Adds `dayN => DayN,` to the `days!` list, keeping it sorted by day
+fn list(lib: &str, day: u8) -> Result<String, String> {
+    let start = lib
+        .find(REGISTRY_START)
+        .ok_or_else(|| format!("no `{REGISTRY_START}` list found"))?
+        + REGISTRY_START.len();
+    let end = start
+        + lib[start..]
+            .find('}')
+            .ok_or_else(|| "the days! list is not closed".to_owned())?;
+    let mut days = vec![];
+    for entry in lib[start..end].split(',').map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+        let registered = entry
+            .split("=>")
+            .next()
+            .and_then(|module| module.trim().strip_prefix("day"))
+            .and_then(|number| number.parse::<u8>().ok())
+            .ok_or_else(|| format!("unexpected entry in the days! list: {entry}"))?;
+        if registered == day {
+            return Err(format!("day {day} is already registered"));
+        }
+        days.push((registered, entry.to_owned()));
+    }
+    days.push((day, format!("day{day} => Day{day}")));
+    days.sort();
+    let entries = days
+        .iter()
+        .map(|(_, entry)| format!("    {entry},\n"))
+        .collect::<String>();
+    Ok(format!("{}\n{entries}{}", &lib[..start], &lib[end..]))
+}
+
+/// Writes the new day's files into `src` and registers it in `src/lib.rs`. Nothing is written if
+/// the day's directory already exists or the day is already registered.
+pub fn scaffold(src: &Path, day: u8) -> Result<Vec<PathBuf>, Failure> {
+    if !(1..=25).contains(&day) {
+        return Err(Failure::UnknownDay(day.to_string()));
+    }
+    let error = |message: String| Failure::Scaffold(message);
+    let dir = src.join(format!("day{day}"));
+    if dir.exists() {
+        return Err(error(format!("{} already exists", dir.display())));
+    }
+    let lib_path = src.join("lib.rs");
+    let lib = fs::read_to_string(&lib_path)
+        .map_err(|io_error| error(format!("{}: {io_error}", lib_path.display())))?;
+    let lib = register(&lib, day).map_err(error)?;
+
+    fs::create_dir(&dir).map_err(|io_error| error(format!("{}: {io_error}", dir.display())))?;
+    let mut written = vec![];
+    for (path, contents) in files(day) {
+        let path = src.join(path);
+        fs::write(&path, contents)
+            .map_err(|io_error| error(format!("{}: {io_error}", path.display())))?;
+        written.push(path);
+    }
+    fs::write(&lib_path, lib)
+        .map_err(|io_error| error(format!("{}: {io_error}", lib_path.display())))?;
+    written.push(lib_path);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod test_scaffold {
+    use super::*;
+    use std::env;
+
+    const MAIN: &str = "mod cli;
+pub mod day1;
+pub mod day3;
+
+solution::days! {
+    day1 => Day1,
+    day3 => Day3,
+}
+
+fn main() {}
+";
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            register(MAIN, 2).unwrap(),
+            "mod cli;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+
+solution::days! {
+    day1 => Day1,
+    day2 => Day2,
+    day3 => Day3,
+}
+
+fn main() {}
+"
+        );
+        assert_eq!(
+            register(MAIN, 3),
+            Err("day 3 is already registered".to_owned())
+        );
+        assert!(register("fn main() {}", 2).is_err());
+        assert!(register(MAIN, 5)
+            .unwrap()
+            .contains("pub mod day3;\npub mod day5;\n\n"));
+        assert_eq!(
+            register("solution::days! {\n}\n", 1).unwrap(),
+            "pub mod day1;\nsolution::days! {\n    day1 => Day1,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_refuses_to_overwrite() {
+        let src = env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(src.join("day3")).unwrap();
+        fs::write(src.join("lib.rs"), MAIN).unwrap();
+
+        assert!(matches!(scaffold(&src, 3), Err(Failure::Scaffold(_))));
+        assert!(matches!(scaffold(&src, 26), Err(Failure::UnknownDay(_))));
+
+        let written = scaffold(&src, 2).unwrap();
+        assert_eq!(written.len(), 5);
+        assert!(src.join("day2/puzzle.rs").exists());
+        let lib = fs::read_to_string(src.join("lib.rs")).unwrap();
+        assert!(lib.contains("pub mod day2;") && lib.contains("day2 => Day2,"));
+        assert!(matches!(scaffold(&src, 2), Err(Failure::Scaffold(_))));
+
+        fs::remove_dir_all(&src).unwrap();
+    }
+}
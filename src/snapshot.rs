@@ -0,0 +1,107 @@
+//! Snapshot tests: the `Display` output of a value is compared against a file under `snapshots/`,
+//! and running the tests with `AOC_BLESS=1` rewrites the files instead
+
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Set to anything but `0` to write the current output to the snapshots rather than check it
+pub const BLESS_ENV: &str = "AOC_BLESS";
+
+fn snapshot_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{name}.txt"))
+}
+
+fn blessing() -> bool {
+    env::var_os(BLESS_ENV).is_some_and(|value| value != "0")
+}
+
+/// Fails with a line diff unless `actual` displays exactly as the snapshot called `name`
+#[track_caller]
+pub fn assert_snapshot(name: &str, actual: impl Display) {
+    let path = snapshot_path(name);
+    let actual = format!("{actual}\n");
+    if blessing() {
+        fs::create_dir_all(path.parent().expect("snapshots have a directory")).unwrap();
+        fs::write(&path, &actual).unwrap_or_else(|error| panic!("{}: {error}", path.display()));
+        return;
+    }
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => panic!(
+            "no snapshot at {}; run with {BLESS_ENV}=1 to create it",
+            path.display()
+        ),
+        Err(error) => panic!("{}: {error}", path.display()),
+    };
+    if expected != actual {
+        panic!(
+            "{} does not match (- snapshot, + actual):\n{}\nrun with {BLESS_ENV}=1 to accept it",
+            path.display(),
+            diff(&expected, &actual)
+        );
+    }
+}
+
+/// Every line of both texts, prefixed with `-` when only in `expected`, `+` when only in `actual`
+/// and spaces when in both, lined up on their longest common subsequence
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    // common[i][j]: length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push(format!("  {}", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+        {
+            lines.push(format!("- {}", expected[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", actual[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test_snapshot {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(
+            diff("..#\n.^.\n...\n", "..#\n.>.\n...\n#..\n"),
+            "  ..#\n- .^.\n+ .>.\n  ...\n+ #.."
+        );
+        assert_eq!(diff("a\nb\n", "a\nb\n"), "  a\n  b");
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match")]
+    fn test_mismatch() {
+        if blessing() {
+            panic!("does not match, as blessing would overwrite the snapshot");
+        }
+        assert_snapshot("day6/example", "....#.....");
+    }
+}
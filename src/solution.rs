@@ -0,0 +1,173 @@
+use crate::error;
+use crate::inspect::Stat;
+use std::any::Any;
+use std::fmt::{self, Display};
+use std::io::BufRead;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(u64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($number:ty),*) => {
+        $(impl From<$number> for Answer {
+            fn from(number: $number) -> Self {
+                Answer::Number(number as u64)
+            }
+        })*
+    };
+}
+answer_from_number!(u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_owned())
+    }
+}
+
+/// A day's puzzle. The input is parsed once and then shared by both parts. Malformed input and
+/// inputs without an answer are reported as errors rather than panics.
+pub trait Solution {
+    type Input;
+
+    const DAY: u8;
+    /// Whether the input is a grid of characters, so that `aoc inspect` checks every line is as wide
+    const GRID: bool = false;
+
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> error::Result<Answer>;
+    fn part2(&self, input: &Self::Input) -> error::Result<Answer>;
+
+    // A human-readable rendering of the parsed input, for days whose input can be displayed
+    fn show(&self, _input: &Self::Input) -> Option<String> {
+        None
+    }
+
+    // Figures about the shape of the parsed input for `aoc inspect`, on top of the line and grid
+    // checks every day gets
+    fn inspect(&self, _input: &Self::Input) -> Vec<Stat> {
+        vec![]
+    }
+}
+
+/// `Solution` with the input type erased, so that every day can sit in the same registry
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Box<dyn Any>>;
+    fn part1(&self, input: &dyn Any) -> error::Result<Answer>;
+    fn part2(&self, input: &dyn Any) -> error::Result<Answer>;
+    fn show(&self, input: &dyn Any) -> Option<String>;
+    fn grid(&self) -> bool;
+    fn inspect(&self, input: &dyn Any) -> Vec<Stat>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+    fn parse(&self, reader: &mut dyn BufRead) -> error::Result<Box<dyn Any>> {
+        Ok(Box::new(Solution::parse(self, reader)?))
+    }
+    fn part1(&self, input: &dyn Any) -> error::Result<Answer> {
+        Solution::part1(self, downcast::<S>(input))
+    }
+    fn part2(&self, input: &dyn Any) -> error::Result<Answer> {
+        Solution::part2(self, downcast::<S>(input))
+    }
+    fn show(&self, input: &dyn Any) -> Option<String> {
+        Solution::show(self, downcast::<S>(input))
+    }
+    fn grid(&self) -> bool {
+        S::GRID
+    }
+    fn inspect(&self, input: &dyn Any) -> Vec<Stat> {
+        Solution::inspect(self, downcast::<S>(input))
+    }
+}
+
+fn downcast<S: Solution>(input: &dyn Any) -> &S::Input
+where
+    S::Input: 'static,
+{
+    input
+        .downcast_ref()
+        .unwrap_or_else(|| panic!("input was not parsed by day {}", S::DAY))
+}
+
+/// Lists each day's solution in `SOLUTIONS`, in day order. The day's module is declared next to
+/// the others, so that rustfmt reaches it.
+macro_rules! days {
+    ($($module:ident => $solution:ident),* $(,)?) => {
+        pub static SOLUTIONS: &[&dyn $crate::solution::DynSolution] = &[$(&$module::$solution),*];
+    };
+}
+pub(crate) use days;
+
+pub fn find(solutions: &[&'static dyn DynSolution], day: u8) -> Option<&'static dyn DynSolution> {
+    solutions
+        .iter()
+        .find(|solution| solution.day() == day)
+        .copied()
+}
+
+#[cfg(test)]
+mod test_dyn_solution {
+    use super::*;
+
+    struct Doubler;
+
+    impl Solution for Doubler {
+        type Input = u32;
+        const DAY: u8 = 42;
+
+        fn parse(&self, _reader: &mut dyn BufRead) -> error::Result<u32> {
+            Ok(21)
+        }
+        fn part1(&self, input: &u32) -> error::Result<Answer> {
+            Ok((input * 2).into())
+        }
+        fn part2(&self, input: &u32) -> error::Result<Answer> {
+            Ok(format!("{input}!").into())
+        }
+    }
+
+    #[test]
+    fn test_erased_round_trip() {
+        let solution: &dyn DynSolution = &Doubler;
+        let input = solution.parse(&mut std::io::empty()).unwrap();
+        assert_eq!(solution.day(), 42);
+        assert_eq!(solution.part1(input.as_ref()).unwrap(), Answer::Number(42));
+        assert_eq!(
+            solution.part2(input.as_ref()).unwrap(),
+            Answer::Text("21!".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        static SOLUTIONS: &[&dyn DynSolution] = &[&Doubler];
+        assert_eq!(find(SOLUTIONS, 42).map(|solution| solution.day()), Some(42));
+        assert!(find(SOLUTIONS, 1).is_none());
+    }
+}
@@ -0,0 +1,352 @@
+use crate::cli::{Failure, Part};
+use crate::client::Client;
+use crate::log;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// What the site made of a submitted answer
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without saying which way
+    Wrong,
+    /// An answer was given too recently; nothing was checked
+    Wait(Duration),
+    /// The part has been solved already; nothing was checked
+    AlreadySolved,
+}
+
+impl Verdict {
+    // The name used in the submissions file, for the verdicts worth remembering
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            Verdict::Correct => Some("correct"),
+            Verdict::TooHigh => Some("too-high"),
+            Verdict::TooLow => Some("too-low"),
+            Verdict::Wrong => Some("wrong"),
+            Verdict::Wait(_) | Verdict::AlreadySolved => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "correct" => Some(Verdict::Correct),
+            "too-high" => Some(Verdict::TooHigh),
+            "too-low" => Some(Verdict::TooLow),
+            "wrong" => Some(Verdict::Wrong),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::Wait(wait) => write!(f, "submitted too recently, wait {}s", wait.as_secs()),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// Reads the verdict out of the page the site answers a submission with
+pub fn parse_response(page: &str) -> Option<Verdict> {
+    if page.contains("That's the right answer") {
+        Some(Verdict::Correct)
+    } else if page.contains("your answer is too high") {
+        Some(Verdict::TooHigh)
+    } else if page.contains("your answer is too low") {
+        Some(Verdict::TooLow)
+    } else if page.contains("That's not the right answer") {
+        Some(Verdict::Wrong)
+    } else if page.contains("You gave an answer too recently") {
+        Some(Verdict::Wait(
+            parse_wait(page).unwrap_or(Duration::from_secs(60)),
+        ))
+    } else if page.contains("Did you already complete it") {
+        Some(Verdict::AlreadySolved)
+    } else {
+        None
+    }
+}
+
+// "You have 4m 32s left to wait."
+fn parse_wait(page: &str) -> Option<Duration> {
+    let (before, _) = page.split_once(" left to wait")?;
+    let (_, wait) = before.rsplit_once("You have ")?;
+    wait.split_whitespace()
+        .map(|amount| {
+            let (number, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+            let number: u64 = number.parse().ok()?;
+            match unit {
+                "h" => Some(number * 3600),
+                "m" => Some(number * 60),
+                "s" => Some(number),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+/// One line of the submissions file: `<year> <day> <part> <answer> <verdict>`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Submission {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Every answer submitted so far, so that none is sent twice
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Submissions {
+    submissions: Vec<Submission>,
+}
+
+impl Submissions {
+    // A missing file is an empty record; lines that can't be read are skipped
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        Ok(Submissions::parse(&contents))
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let submissions = contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                Some(Submission {
+                    year: fields.next()?.parse().ok()?,
+                    day: fields.next()?.parse().ok()?,
+                    part: fields.next()?.parse().ok()?,
+                    answer: fields.next()?.to_owned(),
+                    verdict: Verdict::from_name(fields.next()?)?,
+                })
+            })
+            .collect();
+        Submissions { submissions }
+    }
+
+    /// Why the answer shouldn't be sent, if it has been judged already or falls outside an
+    /// earlier too high or too low
+    pub fn check(&self, year: u16, day: u8, part: Part, answer: &str) -> Option<String> {
+        let number = answer.parse::<i128>().ok();
+        self.submissions
+            .iter()
+            .filter(|submission| {
+                (submission.year, submission.day, submission.part) == (year, day, part.number())
+            })
+            .find_map(|submission| {
+                let earlier = submission.answer.parse::<i128>().ok();
+                match (&submission.verdict, number, earlier) {
+                    (Verdict::Correct, ..) if submission.answer == answer => {
+                        Some(format!("{answer} was already accepted"))
+                    }
+                    (Verdict::Correct, ..) => {
+                        Some(format!("already solved with {}", submission.answer))
+                    }
+                    (verdict, ..) if submission.answer == answer => {
+                        Some(format!("{answer} was already submitted: {verdict}"))
+                    }
+                    (Verdict::TooHigh, Some(number), Some(earlier)) if number > earlier => Some(
+                        format!("{answer} is above {earlier}, which was already too high"),
+                    ),
+                    (Verdict::TooLow, Some(number), Some(earlier)) if number < earlier => Some(
+                        format!("{answer} is below {earlier}, which was already too low"),
+                    ),
+                    _ => None,
+                }
+            })
+    }
+
+    /// The answer the site accepted for the part, if any
+    pub fn accepted(&self, year: u16, day: u8, part: Part) -> Option<&str> {
+        self.submissions
+            .iter()
+            .find(|submission| {
+                (submission.year, submission.day, submission.part) == (year, day, part.number())
+                    && submission.verdict == Verdict::Correct
+            })
+            .map(|submission| submission.answer.as_str())
+    }
+
+    pub fn record(&mut self, path: &Path, submission: Submission) -> io::Result<()> {
+        if let Some(verdict) = submission.verdict.name() {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(
+                file,
+                "{} {} {} {} {verdict}",
+                submission.year, submission.day, submission.part, submission.answer
+            )?;
+            self.submissions.push(submission);
+        }
+        Ok(())
+    }
+}
+
+/// Posts the answer unless the submissions file says it would be wasted, and records the verdict
+pub fn submit(
+    client: &Client,
+    submissions_path: &Path,
+    year: u16,
+    day: u8,
+    part: Part,
+    answer: &str,
+) -> Result<Verdict, Failure> {
+    let file_error =
+        |error: io::Error| Failure::Network(format!("{}: {error}", submissions_path.display()));
+    let mut submissions = Submissions::load(submissions_path).map_err(file_error)?;
+    if let Some(reason) = submissions.check(year, day, part, answer) {
+        return Err(Failure::Rejected(format!("{reason}, so it wasn't sent")));
+    }
+    if answer.contains(char::is_whitespace) {
+        return Err(Failure::Rejected(format!(
+            "{answer:?} has whitespace in it, so it wasn't sent"
+        )));
+    }
+    log::debug!("submitting {answer} for day {day} part {}", part.number());
+    let response = client.post(
+        &format!("/{year}/day/{day}/answer"),
+        &format!("level={}&answer={}", part.number(), encode(answer)),
+    )?;
+    if response.status != 200 {
+        return Err(Failure::Network(format!("HTTP {}", response.status)));
+    }
+    let verdict = parse_response(&response.body)
+        .ok_or_else(|| Failure::Network("couldn't make sense of the response".to_owned()))?;
+    submissions
+        .record(
+            submissions_path,
+            Submission {
+                year,
+                day,
+                part: part.number(),
+                answer: answer.to_owned(),
+                verdict: verdict.clone(),
+            },
+        )
+        .map_err(file_error)?;
+    Ok(verdict)
+}
+
+// Percent-encodes everything but the characters that are safe in a form value
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_submit {
+    use super::*;
+    use crate::http::stub;
+    use std::env;
+
+    fn page(article: &str) -> String {
+        format!("<html><body><main><article><p>{article}</p></article></main></body></html>")
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let cases = [
+            (
+                "That's the right answer!  You are one gold star closer.",
+                Verdict::Correct,
+            ),
+            (
+                "That's not the right answer; your answer is too high.  Please wait one minute.",
+                Verdict::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                Verdict::TooLow,
+            ),
+            (
+                "That's not the right answer.  If you're stuck, ...",
+                Verdict::Wrong,
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 4m 32s left to wait.",
+                Verdict::Wait(Duration::from_secs(272)),
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                Verdict::AlreadySolved,
+            ),
+        ];
+        for (article, verdict) in cases {
+            assert_eq!(parse_response(&page(article)), Some(verdict));
+        }
+        assert_eq!(parse_response("<html></html>"), None);
+    }
+
+    #[test]
+    fn test_check() {
+        let submissions = Submissions::parse(
+            "2024 6 1 5000 too-high\n2024 6 1 10 too-low\n2024 6 2 7 correct\nnonsense\n",
+        );
+        let check = |part, answer| submissions.check(2024, 6, part, answer);
+        assert_eq!(check(Part::One, "4602"), None);
+        assert!(check(Part::One, "5000")
+            .unwrap()
+            .contains("already submitted"));
+        assert!(check(Part::One, "6000").unwrap().contains("too high"));
+        assert!(check(Part::One, "3").unwrap().contains("too low"));
+        assert!(check(Part::Two, "8").unwrap().contains("already solved"));
+        assert_eq!(submissions.check(2023, 6, Part::One, "5000"), None);
+        assert_eq!(submissions.accepted(2024, 6, Part::Two), Some("7"));
+        assert_eq!(submissions.accepted(2024, 6, Part::One), None);
+    }
+
+    #[test]
+    fn test_submit() {
+        let path = env::temp_dir().join(format!("aoc-submissions-{}.txt", std::process::id()));
+        let (url, server) = stub::serve(vec![stub::response(
+            200,
+            &page("That's not the right answer; your answer is too low."),
+        )]);
+        let client = Client::new(&url, "abc".to_owned()).throttle(Duration::ZERO, None);
+
+        assert!(matches!(
+            submit(&client, &path, 2024, 3, Part::Two, "42"),
+            Ok(Verdict::TooLow)
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "2024 3 2 42 too-low\n");
+        // Refused locally; the stub would fail the test if asked again
+        assert!(matches!(
+            submit(&client, &path, 2024, 3, Part::Two, "41"),
+            Err(Failure::Rejected(_))
+        ));
+
+        let request = &server.join().unwrap()[0];
+        assert!(request.starts_with("POST /2024/day/3/answer HTTP/1.0\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=42"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("a,b 1"), "a%2Cb%201");
+    }
+}
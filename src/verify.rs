@@ -0,0 +1,352 @@
+use crate::cli::{Part, RunOptions};
+use crate::input::{InputResolver, INPUT_FILE};
+use crate::runner::{self, PartReport};
+use crate::sha256;
+use crate::solution::{Answer, DynSolution};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::process;
+use std::time::SystemTime;
+
+/// Expected answers keyed by day, input file name and part. Parsed from lines of
+/// `<day> <input file> <part> <answer>`, where `#` starts a comment.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct ExpectedAnswers {
+    answers: BTreeMap<(u8, String, u8), Expected>,
+}
+
+/// An expected answer, either written out or as a salted digest that can be committed without
+/// giving the answer away
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Expected {
+    Plain(String),
+    /// Written `sha256:<salt>:<digest>`, the digest being that of the salt followed by the answer
+    Digest {
+        salt: String,
+        digest: String,
+    },
+}
+
+impl Expected {
+    const DIGEST_PREFIX: &str = "sha256:";
+
+    /// A digest of the answer with a fresh salt
+    pub fn hashed(answer: &str) -> Self {
+        let salt = new_salt();
+        let digest = sha256::hex(&sha256::digest(format!("{salt}{answer}").as_bytes()));
+        Expected::Digest { salt, digest }
+    }
+
+    fn parse(field: &str) -> Option<Self> {
+        let Some(hashed) = field.strip_prefix(Self::DIGEST_PREFIX) else {
+            return Some(Expected::Plain(field.to_owned()));
+        };
+        let (salt, digest) = hashed.split_once(':')?;
+        let is_hex = |value: &str| value.chars().all(|c| c.is_ascii_hexdigit());
+        (is_hex(salt) && is_hex(digest) && digest.len() == 64).then(|| Expected::Digest {
+            salt: salt.to_owned(),
+            digest: digest.to_ascii_lowercase(),
+        })
+    }
+
+    pub fn matches(&self, answer: &str) -> bool {
+        match self {
+            Expected::Plain(expected) => expected == answer,
+            Expected::Digest { salt, digest } => {
+                sha256::hex(&sha256::digest(format!("{salt}{answer}").as_bytes())) == *digest
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Plain(answer) => write!(f, "{answer}"),
+            Expected::Digest { salt, digest } => {
+                write!(f, "{}{salt}:{digest}", Self::DIGEST_PREFIX)
+            }
+        }
+    }
+}
+
+// Sixteen hex digits that differ between answers and between runs. A salt only has to keep equal
+// answers from having equal digests, so it needn't be unpredictable.
+fn new_salt() -> String {
+    thread_local! {
+        static COUNTER: Cell<u64> = const { Cell::new(0) };
+    }
+    let count = COUNTER.replace(COUNTER.get() + 1);
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos());
+    let seed = format!("{nanos}:{}:{count}", process::id());
+    sha256::hex(&sha256::digest(seed.as_bytes())[..8])
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseAnswersError {
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for ParseAnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl ExpectedAnswers {
+    pub fn parse(contents: &str) -> Result<Self, ParseAnswersError> {
+        let mut answers = BTreeMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            let error = |message: &str| ParseAnswersError {
+                line: index + 1,
+                message: message.to_owned(),
+            };
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.splitn(4, char::is_whitespace);
+            let day = fields
+                .next()
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| error("day is not a number"))?;
+            let input = fields.next().ok_or_else(|| error("missing input file"))?;
+            let part = match fields.next() {
+                Some("1") => 1,
+                Some("2") => 2,
+                _ => return Err(error("part must be 1 or 2")),
+            };
+            let answer = fields
+                .next()
+                .map(str::trim)
+                .filter(|answer| !answer.is_empty())
+                .ok_or_else(|| error("missing answer"))?;
+            let expected = Expected::parse(answer).ok_or_else(|| error("malformed digest"))?;
+            answers.insert((day, input.to_owned(), part), expected);
+        }
+        Ok(ExpectedAnswers { answers })
+    }
+
+    pub fn get(&self, day: u8, input: &str, part: Part) -> Option<&Expected> {
+        self.answers.get(&(day, input.to_owned(), part.number()))
+    }
+
+    // Every input file with an expected answer for the given day, plus the real input
+    fn inputs(&self, day: u8) -> Vec<String> {
+        let mut inputs = vec![INPUT_FILE.to_owned()];
+        for (answer_day, input, _) in self.answers.keys() {
+            if *answer_day == day && !inputs.contains(input) {
+                inputs.push(input.clone());
+            }
+        }
+        inputs
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Missing,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Missing => "MISSING",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub input: String,
+    pub part: Part,
+    pub expected: Option<Expected>,
+    pub actual: Result<Answer, String>,
+}
+
+impl Check {
+    // A solver error is always a failure, even when there's nothing to compare it to
+    pub fn status(&self) -> Status {
+        match (&self.expected, &self.actual) {
+            (_, Err(_)) => Status::Fail,
+            (None, Ok(_)) => Status::Missing,
+            (Some(expected), Ok(actual)) if expected.matches(&actual.to_string()) => Status::Pass,
+            (Some(_), Ok(_)) => Status::Fail,
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let actual = match &self.actual {
+            Ok(answer) => answer.to_string(),
+            Err(message) => format!("ERROR: {message}"),
+        };
+        write!(
+            f,
+            "{:<7}  day {} {} part {}  ",
+            self.status(),
+            self.day,
+            self.input,
+            self.part.number()
+        )?;
+        match (self.status(), &self.expected) {
+            (Status::Pass, _) => write!(f, "{actual}"),
+            // Printing the digest would tell nobody anything
+            (_, Some(Expected::Digest { .. })) => {
+                write!(f, "got {actual}, which doesn't match the recorded digest")
+            }
+            (_, Some(expected)) => write!(f, "expected {expected}, got {actual}"),
+            (_, None) => write!(f, "got {actual}"),
+        }
+    }
+}
+
+/// Runs both parts of every solution against each input that has expected answers
+pub fn verify(
+    solutions: &[&'static dyn DynSolution],
+    resolver: &InputResolver,
+    expected: &ExpectedAnswers,
+) -> Vec<Check> {
+    let mut checks = vec![];
+    for solution in solutions {
+        let day = solution.day();
+        for input in expected.inputs(day) {
+            let options = RunOptions {
+                input: Some(resolver.path(day, &input)),
+                ..RunOptions::default()
+            };
+            // Panics end up in the FAIL lines, so their messages and backtraces aren't printed
+            let report = runner::quietly(|| runner::run(*solution, &options));
+            let parse_error = report.parse.as_ref().err().map(|error| error.to_string());
+            for (part, part_report) in [(Part::One, report.part1), (Part::Two, report.part2)] {
+                let actual = match (part_report, &parse_error) {
+                    (Some(PartReport { answer, .. }), _) => answer,
+                    (None, Some(message)) => Err(message.clone()),
+                    (None, None) => Err("part was not run".to_owned()),
+                };
+                checks.push(Check {
+                    day,
+                    input: input.clone(),
+                    part,
+                    expected: expected.get(day, &input, part).cloned(),
+                    actual,
+                });
+            }
+        }
+    }
+    checks
+}
+
+#[cfg(test)]
+mod expected_answers {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let expected = ExpectedAnswers::parse(
+            "# day input part answer\n\n6 example.txt 1 41\n6 example.txt 2 6 # loops\n",
+        )
+        .unwrap();
+        let plain = |answer: &str| Some(Expected::Plain(answer.to_owned()));
+        assert_eq!(
+            expected.get(6, "example.txt", Part::One),
+            plain("41").as_ref()
+        );
+        assert_eq!(
+            expected.get(6, "example.txt", Part::Two),
+            plain("6").as_ref()
+        );
+        assert_eq!(expected.get(6, "input.txt", Part::One), None);
+        assert_eq!(
+            expected.inputs(6),
+            vec!["input.txt".to_owned(), "example.txt".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            ExpectedAnswers::parse("6 example.txt 1 41\n6 example.txt 3 6"),
+            Err(ParseAnswersError {
+                line: 2,
+                message: "part must be 1 or 2".to_owned()
+            })
+        );
+        assert!(ExpectedAnswers::parse("six example.txt 1 41").is_err());
+        assert!(ExpectedAnswers::parse("6 example.txt 1").is_err());
+        assert!(ExpectedAnswers::parse("6 example.txt 1 sha256:00ff:1234").is_err());
+    }
+
+    #[test]
+    fn test_digest() {
+        // SHA-256 of "0123456789abcdef41"
+        let line = "6 example.txt 1 sha256:0123456789abcdef:\
+                    43a7b2160b84fe125f0d5c6313989db8b95c2eafbbb03a3c0bab603ab081756d";
+        let expected = ExpectedAnswers::parse(line).unwrap();
+        let expected = expected.get(6, "example.txt", Part::One).unwrap();
+        assert!(expected.matches("41"));
+        assert!(!expected.matches("42"));
+        assert_eq!(expected.to_string(), line[16..]);
+
+        let hashed = Expected::hashed("41");
+        assert!(hashed.matches("41"));
+        assert_ne!(hashed, Expected::hashed("41"));
+        assert_eq!(Expected::parse(&hashed.to_string()), Some(hashed));
+    }
+}
+
+#[cfg(test)]
+mod check {
+    use super::*;
+
+    fn check(expected: Option<&str>, actual: Result<Answer, String>) -> Check {
+        Check {
+            day: 5,
+            input: "example.txt".to_owned(),
+            part: Part::Two,
+            expected: expected.map(|answer| Expected::Plain(answer.to_owned())),
+            actual,
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(check(Some("123"), Ok(123u32.into())).status(), Status::Pass);
+        assert_eq!(check(Some("123"), Ok(124u32.into())).status(), Status::Fail);
+        assert_eq!(check(None, Ok(124u32.into())).status(), Status::Missing);
+        assert_eq!(
+            check(None, Err("not implemented".to_owned())).status(),
+            Status::Fail
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            check(Some("123"), Err("not implemented".to_owned())).to_string(),
+            "FAIL     day 5 example.txt part 2  expected 123, got ERROR: not implemented"
+        );
+        assert_eq!(
+            check(Some("123"), Ok(123u32.into())).to_string(),
+            "PASS     day 5 example.txt part 2  123"
+        );
+        let hashed = Check {
+            expected: Some(Expected::hashed("123")),
+            ..check(None, Ok(124u32.into()))
+        };
+        assert_eq!(
+            hashed.to_string(),
+            "FAIL     day 5 example.txt part 2  got 124, which doesn't match the recorded digest"
+        );
+    }
+}
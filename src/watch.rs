@@ -0,0 +1,229 @@
+use crate::alloc::AllocStats;
+use crate::cli::{Failure, RunOptions};
+use crate::input::InputResolver;
+use crate::runner::{self, format_duration, DayReport, PartReport};
+use crate::solution::DynSolution;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Remembers the size and modification time of every watched file so that changes can be found by
+/// polling, without any OS-specific notification API
+pub struct Watcher {
+    dir: PathBuf,
+    extra: Option<PathBuf>,
+    seen: BTreeMap<PathBuf, Option<(u64, SystemTime)>>,
+}
+
+impl Watcher {
+    // Watches every file in `dir`, including ones created later, plus `extra` if given
+    pub fn new(dir: PathBuf, extra: Option<PathBuf>) -> Self {
+        let mut watcher = Watcher {
+            dir,
+            extra,
+            seen: BTreeMap::new(),
+        };
+        watcher.seen = watcher.scan();
+        watcher
+    }
+
+    fn scan(&self) -> BTreeMap<PathBuf, Option<(u64, SystemTime)>> {
+        let mut paths = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .map_while(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_file())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        paths.extend(self.extra.clone());
+        paths
+            .into_iter()
+            .map(|path| {
+                let stamp = fs::metadata(&path)
+                    .and_then(|metadata| Ok((metadata.len(), metadata.modified()?)))
+                    .ok();
+                (path, stamp)
+            })
+            .collect()
+    }
+
+    /// Files that were added, removed or modified since the last poll
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let current = self.scan();
+        let mut changed = current
+            .iter()
+            .filter(|(path, stamp)| self.seen.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        changed.extend(
+            self.seen
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .cloned(),
+        );
+        self.seen = current;
+        changed
+    }
+}
+
+// The text shown for each part, so that consecutive runs can be compared
+fn answers(report: &DayReport) -> [Option<String>; 2] {
+    let answer = |part: &Option<PartReport>| {
+        part.as_ref().map(|part| match &part.answer {
+            Ok(answer) => answer.to_string(),
+            Err(message) => format!("ERROR: {message}"),
+        })
+    };
+    match &report.parse {
+        Ok(_) => [answer(&report.part1), answer(&report.part2)],
+        Err(failure) => [Some(format!("ERROR: {failure}")), None],
+    }
+}
+
+/// Answers and timings of the latest run, each answer marked against the one before it
+pub fn format_run(report: &DayReport, previous: Option<&[Option<String>; 2]>) -> String {
+    let mut lines = vec![];
+    match &report.parse {
+        Ok(time) => lines.push(format!(
+            "Parse: {}{}",
+            format_duration(*time),
+            format_alloc(report.parse_alloc)
+        )),
+        Err(failure) => lines.push(format!("ERROR: {failure}")),
+    }
+    let current = answers(report);
+    let parts = [&report.part1, &report.part2];
+    for (index, answer) in current.iter().enumerate() {
+        let (Some(answer), Some(part)) = (answer, parts[index]) else {
+            continue;
+        };
+        let change = match previous.map(|previous| &previous[index]) {
+            None => String::new(),
+            Some(Some(previous)) if previous == answer => " (unchanged)".to_owned(),
+            Some(Some(previous)) => format!(" (was {previous})"),
+            Some(None) => " (new)".to_owned(),
+        };
+        lines.push(format!(
+            "Part{}: {answer} in {}{}{change}",
+            index + 1,
+            format_duration(part.time),
+            format_alloc(part.alloc)
+        ));
+    }
+    lines.join("\n")
+}
+
+fn format_alloc(stats: Option<AllocStats>) -> String {
+    stats.map_or_else(String::new, |stats| format!(" ({stats})"))
+}
+
+/// Re-runs the day every time one of its files changes, until the process is interrupted
+pub fn watch(
+    solution: &dyn DynSolution,
+    options: &RunOptions,
+    interval: Duration,
+) -> Result<(), Failure> {
+    let day = solution.day();
+    let dir = InputResolver::new(options.input_dir.clone()).path(day, "");
+    let mut watcher = Watcher::new(dir.clone(), options.input.clone());
+    let mut previous = None;
+    let mut changed: Vec<PathBuf> = vec![];
+    loop {
+        let report = runner::run(solution, options);
+        print!("{CLEAR_SCREEN}");
+        println!("Day {day}, watching {}", dir.display());
+        for path in changed.iter() {
+            println!("Changed: {}", path.display());
+        }
+        if changed.iter().any(|path| is_source(path)) {
+            println!("Source files changed; rebuild to pick up code changes");
+        }
+        println!("{}", format_run(&report, previous.as_ref()));
+        previous = Some(answers(&report));
+
+        changed = loop {
+            thread::sleep(interval);
+            let changed = watcher.poll();
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+    }
+}
+
+fn is_source(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "rs")
+}
+
+#[cfg(test)]
+mod watcher {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_poll() {
+        let dir = env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("input.txt"), "1").unwrap();
+        let mut watcher = Watcher::new(dir.clone(), None);
+        assert!(watcher.poll().is_empty());
+
+        fs::write(dir.join("input.txt"), "12").unwrap();
+        assert_eq!(watcher.poll(), vec![dir.join("input.txt")]);
+        assert!(watcher.poll().is_empty());
+
+        fs::write(dir.join("example.txt"), "1").unwrap();
+        assert_eq!(watcher.poll(), vec![dir.join("example.txt")]);
+        fs::remove_file(dir.join("example.txt")).unwrap();
+        assert_eq!(watcher.poll(), vec![dir.join("example.txt")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test_format_run {
+    use super::*;
+    use crate::solution::Answer;
+
+    fn report(part1: u64, part2: Result<Answer, String>) -> DayReport {
+        DayReport {
+            parse: Ok(Duration::from_micros(5)),
+            parse_alloc: None,
+            part1: Some(PartReport {
+                answer: Ok(Answer::Number(part1)),
+                time: Duration::from_micros(7),
+                alloc: None,
+            }),
+            part2: Some(PartReport {
+                answer: part2,
+                time: Duration::from_micros(9),
+                alloc: None,
+            }),
+            phases: vec![],
+        }
+    }
+
+    #[test]
+    fn test_first_run() {
+        assert_eq!(
+            format_run(&report(41, Ok(Answer::Number(6))), None),
+            "Parse: 5µs\nPart1: 41 in 7µs\nPart2: 6 in 9µs"
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let previous = answers(&report(41, Err("not implemented".to_owned())));
+        assert_eq!(
+            format_run(&report(41, Ok(Answer::Number(6))), Some(&previous)),
+            "Parse: 5µs\nPart1: 41 in 7µs (unchanged)\nPart2: 6 in 9µs (was ERROR: not implemented)"
+        );
+    }
+}
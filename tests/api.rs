@@ -0,0 +1,38 @@
+use aoc::solution;
+use aoc::{day6, day7, day8};
+use aoc::{Answer, Equation, GuardMap, SOLUTIONS};
+use std::path::PathBuf;
+
+fn example(day: u8) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("src/day{day}/example.txt"))
+}
+
+#[test]
+fn test_models_parse_from_memory() {
+    let guard_map: GuardMap = day6::parser("..#\n.^.\n...".as_bytes()).unwrap();
+    assert_eq!(guard_map.to_string(), "..#\n.^.\n...");
+
+    let equations = day7::parser("190: 10 19".as_bytes()).unwrap();
+    assert_eq!(
+        equations,
+        vec![Equation {
+            result: 190,
+            operands: vec![10, 19]
+        }]
+    );
+
+    let antenna_map = day8::parser("a..\n...\n..a".as_bytes()).unwrap();
+    assert_eq!(antenna_map.clone().calc_antinodes().count_antinodes(), 0);
+}
+
+#[test]
+fn test_registered_solutions() {
+    let solution = solution::find(SOLUTIONS, 6).unwrap();
+    let options = aoc::cli::RunOptions {
+        input: Some(example(6)),
+        ..Default::default()
+    };
+    let report = aoc::run(solution, &options);
+    assert_eq!(report.part1.unwrap().answer, Ok(Answer::Number(41)));
+    assert_eq!(report.part2.unwrap().answer, Ok(Answer::Number(6)));
+}